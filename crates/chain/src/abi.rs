@@ -121,7 +121,7 @@ fn native_type_to_abi_type(tp: &str) -> &str {
         "u64" => "uint64",
         "i128" => "int128",
         "u128" => "uint128",
-        "Varint32" => "varint32",
+        "VarInt32" => "varint32",
         "VarUint32" => "varuint32",
         "f32" => "float32",
         "f64" => "float64",
@@ -150,7 +150,7 @@ fn is_intrinsic_abi_type(name: &str) -> bool {
     match name {
         "bool" | "i8" | "u8" | "i16" | "u16" | "i32" | "u32" | "i64" | "u64" | "f32" | "f64" | "i128" | "u128" |
         "String" |
-        "Varint32" | "VarUint32" | "Float128" | "TimePoint" | "TimePointSec" |
+        "VarInt32" | "VarUint32" | "Float128" | "TimePoint" | "TimePointSec" |
         "BlockTimeStampType" | "Name" | "Checksum160" | "Checksum256" | "Uint256" |
         "Checksum512" | "PublicKey" | "Signature" | "Symbol" | "SymbolCode" | "Asset" |
         "ExtendedAsset"  => {
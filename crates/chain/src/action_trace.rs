@@ -0,0 +1,123 @@
+use serde_json::Value;
+
+use chaintester::client::{
+    ChainTesterError,
+    Result,
+};
+
+use crate::serializer::Packer;
+
+/// A view of an action trace returned by `ChainTester::push_action`
+pub struct ActionTrace<'a> {
+    trace: &'a Value,
+}
+
+fn error<T>(msg: String) -> Result<T> {
+    Err(ChainTesterError{json: None, error_string: Some(msg)})
+}
+
+pub(crate) fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 {
+        return None;
+    }
+    (0..s.len()).step_by(2).map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok()).collect()
+}
+
+impl<'a> ActionTrace<'a> {
+    ///
+    pub fn new(trace: &'a Value) -> Self {
+        Self { trace }
+    }
+
+    /// Returns the action traces of a transaction trace in execution order
+    pub fn from_transaction(tx: &'a Value) -> Vec<ActionTrace<'a>> {
+        match tx["action_traces"].as_array() {
+            Some(traces) => traces.iter().map(ActionTrace::new).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    ///
+    pub fn receiver(&self) -> &str {
+        self.trace["receiver"].as_str().unwrap_or_default()
+    }
+
+    ///
+    pub fn account(&self) -> &str {
+        self.trace["act"]["account"].as_str().unwrap_or_default()
+    }
+
+    ///
+    pub fn name(&self) -> &str {
+        self.trace["act"]["name"].as_str().unwrap_or_default()
+    }
+
+    /// Returns the raw action data, taken from `hex_data`
+    /// or from `data` when the contract has no ABI.
+    pub fn raw_data(&self) -> Result<Vec<u8>> {
+        let act = &self.trace["act"];
+        let hex_data = match act["hex_data"].as_str() {
+            Some(s) => s,
+            None => match act["data"].as_str() {
+                Some(s) => s,
+                None => return error(format!("no raw data in action {}::{}", self.account(), self.name())),
+            }
+        };
+
+        match parse_hex(hex_data) {
+            Some(data) => Ok(data),
+            None => error(format!("invalid hex data in action {}::{}", self.account(), self.name())),
+        }
+    }
+
+    /// Unpacks the action data into `T`
+    pub fn data_as<T: Packer + Default>(&self) -> Result<T> {
+        let data = self.raw_data()?;
+        let mut value = T::default();
+        let size = value.unpack(&data);
+        if size != data.len() {
+            return error(format!("action data has {} bytes, {} bytes unpacked", data.len(), size));
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::Name;
+    use crate::asset::Asset;
+    use crate::serializer::Encoder;
+    use crate::system_actions::Transfer;
+
+    #[test]
+    fn test_data_as() {
+        let transfer = Transfer {
+            from: Name::new("alice"),
+            to: Name::new("bob"),
+            quantity: Asset::from_string("1.0000 EOS"),
+            memo: "hello".into(),
+        };
+
+        let hex_data: String = Encoder::pack(&transfer).iter().map(|b| format!("{:02x}", b)).collect();
+        let tx = serde_json::json!({
+            "action_traces": [{
+                "receiver": "eosio.token",
+                "act": {
+                    "account": "eosio.token",
+                    "name": "transfer",
+                    "data": {"from": "alice", "to": "bob", "quantity": "1.0000 EOS", "memo": "hello"},
+                    "hex_data": hex_data,
+                }
+            }]
+        });
+
+        let traces = ActionTrace::from_transaction(&tx);
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].name(), "transfer");
+        let unpacked: Transfer = traces[0].data_as().unwrap();
+        assert!(unpacked == transfer);
+
+        assert!(traces[0].data_as::<u64>().is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use chaintester::ChainTester;
+use chaintester::client::{
+    BoxedApply,
+    Result,
+};
+
+use crate::name::Name;
+
+/// Arguments of an apply request delivered to a native contract
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ApplyContext {
+    /// the account whose code is executed
+    pub receiver: Name,
+    /// the account the action was originally sent to, the `code` of eosio.cdt
+    pub first_receiver: Name,
+    ///
+    pub action: Name,
+}
+
+impl ApplyContext {
+    ///
+    pub fn new(receiver: u64, first_receiver: u64, action: u64) -> Self {
+        Self {
+            receiver: Name::from_u64(receiver),
+            first_receiver: Name::from_u64(first_receiver),
+            action: Name::from_u64(action),
+        }
+    }
+
+    /// Returns true if the action is delivered to `receiver` by `require_recipient`
+    pub fn is_notification(&self) -> bool {
+        return self.receiver != self.first_receiver;
+    }
+}
+
+/// Same as `ChainTester::set_native_apply`, but `apply` receives an `ApplyContext`
+/// instead of `(receiver, first_receiver, action)`.
+pub fn set_native_apply_context(tester: &mut ChainTester, contract: &str, apply: Option<fn(&ApplyContext)>) -> Result<()> {
+    let apply = apply.map(|apply| -> BoxedApply {
+        Box::new(move |receiver, first_receiver, action| apply(&ApplyContext::new(receiver, first_receiver, action)))
+    });
+    tester.set_native_apply_boxed(contract, apply)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_context() {
+        // alice::transfer delivered to bob by require_recipient
+        let ctx = ApplyContext::new(Name::new("bob").value(), Name::new("alice").value(), Name::new("transfer").value());
+        assert!(ctx.is_notification());
+        assert!(ctx.receiver == Name::new("bob"));
+        assert!(ctx.first_receiver == Name::new("alice"));
+        assert!(ctx.action == Name::new("transfer"));
+        assert!(!ApplyContext{receiver: Name::new("alice"), ..ctx}.is_notification());
+    }
+}
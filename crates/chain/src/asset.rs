@@ -4,6 +4,7 @@ use crate::serializer::{
     Packer,
     Encoder,
     Decoder,
+    DecodeError,
 };
 
 use crate::name::{
@@ -201,6 +202,15 @@ impl Packer for Symbol {
         check(self.code().is_valid(), "Symbol.unpack: bad symbol value");
         return 8;
     }
+
+    ///
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        self.value.try_unpack(data)?;
+        if !self.code().is_valid() {
+            return Err(DecodeError::Malformed);
+        }
+        Ok(8)
+    }
 }
 
 /// A struct representing an asset with an amount and symbol.
@@ -220,6 +230,17 @@ enum AssetStringParseStatus {
     FoundSpace,
 }
 
+/// Error returned by `Asset::from_f64`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FromF64Error {
+    /// the amount is NaN or infinite
+    NotFinite,
+    /// the scaled amount does not fit in an asset amount
+    Overflow,
+    ///
+    InvalidSymbol,
+}
+
 fn is_amount_within_range(amount: i64) -> bool {
     return -MAX_AMOUNT <= amount && amount <= MAX_AMOUNT;
 }
@@ -307,6 +328,45 @@ impl Asset {
         }
     }
 
+    /// Converts `amount` to an asset of `symbol`, scaling it by the precision of the symbol
+    /// and rounding half to even, e.g. `1.005` with a precision of 2 is `1.00`
+    /// since `1.005` is not exactly representable and is slightly below it.
+    pub fn from_f64(amount: f64, symbol: Symbol) -> Result<Self, FromF64Error> {
+        if !amount.is_finite() {
+            return Err(FromF64Error::NotFinite);
+        }
+
+        if !symbol.is_valid() {
+            return Err(FromF64Error::InvalidSymbol);
+        }
+
+        // powers of 10 up to 10^18 are exact
+        let mut scale = 1f64;
+        for _ in 0..symbol.precision() {
+            scale *= 10.0;
+        }
+
+        let scaled = amount * scale;
+        // MAX_AMOUNT + 1 is 2^62, exact as a f64
+        let limit = (MAX_AMOUNT + 1) as f64;
+        if !(scaled > -limit && scaled < limit) {
+            return Err(FromF64Error::Overflow);
+        }
+
+        let truncated = scaled as i64;
+        let fraction = scaled - truncated as f64;
+        let fraction_abs = if fraction < 0.0 { -fraction } else { fraction };
+        let mut rounded = truncated;
+        if fraction_abs > 0.5 || (fraction_abs == 0.5 && truncated % 2 != 0) {
+            rounded += if fraction < 0.0 { -1 } else { 1 };
+        }
+
+        if !is_amount_within_range(rounded) {
+            return Err(FromF64Error::Overflow);
+        }
+        Ok(Self{amount: rounded, symbol})
+    }
+
     ///
     pub fn amount(&self) -> i64 {
         self.amount
@@ -422,6 +482,17 @@ impl Packer for Asset {
         dec.unpack(&mut self.symbol);
         dec.get_pos()
     }
+
+    ///
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut dec = Decoder::new(data);
+        dec.try_unpack(&mut self.amount)?;
+        if !is_amount_within_range(self.amount) {
+            return Err(DecodeError::Malformed);
+        }
+        dec.try_unpack(&mut self.symbol)?;
+        Ok(dec.get_pos())
+    }
 }
 
 /// A struct representing an extended asset with an associated contract.
@@ -477,3 +548,46 @@ impl Packer for ExtendedAsset {
         dec.get_pos()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_f64() {
+        let symbol = Symbol::new("EOS", 2);
+        assert_eq!(Asset::from_f64(1.005, symbol).unwrap().amount(), 100);
+        assert_eq!(Asset::from_f64(1.25, Symbol::new("EOS", 1)).unwrap().amount(), 12);
+        assert_eq!(Asset::from_f64(1.35, Symbol::new("EOS", 1)).unwrap().amount(), 14);
+        assert_eq!(Asset::from_f64(2.5, Symbol::new("EOS", 0)).unwrap().amount(), 2);
+        assert_eq!(Asset::from_f64(3.5, Symbol::new("EOS", 0)).unwrap().amount(), 4);
+
+        let asset = Asset::from_f64(-12.3456, Symbol::new("EOS", 4)).unwrap();
+        assert_eq!(asset.amount(), -123456);
+        assert!(asset.symbol() == Symbol::new("EOS", 4));
+        assert_eq!(Asset::from_f64(-2.5, Symbol::new("EOS", 0)).unwrap().amount(), -2);
+
+        assert_eq!(Asset::from_f64(f64::NAN, symbol).err(), Some(FromF64Error::NotFinite));
+        assert_eq!(Asset::from_f64(f64::INFINITY, symbol).err(), Some(FromF64Error::NotFinite));
+        assert_eq!(Asset::from_f64(1e20, Symbol::new("EOS", 4)).err(), Some(FromF64Error::Overflow));
+        assert_eq!(Asset::from_f64(-1e20, Symbol::new("EOS", 4)).err(), Some(FromF64Error::Overflow));
+    }
+
+    #[test]
+    fn test_asset_try_unpack() {
+        let data = Encoder::pack(&Asset::new(10000, Symbol::new("EOS", 4)));
+        let mut asset = Asset::default();
+        assert_eq!(asset.try_unpack(&data), Ok(16));
+        assert_eq!(asset.to_string(), "1.0000 EOS");
+
+        assert_eq!(asset.try_unpack(&data[..12]), Err(DecodeError::BufferOverflow));
+        // lowercase symbol code
+        let mut bad_symbol = data.clone();
+        bad_symbol[9] = b'e';
+        assert_eq!(asset.try_unpack(&bad_symbol), Err(DecodeError::Malformed));
+        // amount of 2^62
+        let mut bad_amount = data.clone();
+        bad_amount[..8].copy_from_slice(&(1i64 << 62).to_le_bytes());
+        assert_eq!(asset.try_unpack(&bad_amount), Err(DecodeError::Malformed));
+    }
+}
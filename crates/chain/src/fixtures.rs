@@ -0,0 +1,58 @@
+// Packed test fixtures shared with eosio.cdt tests.
+// A fixture file contains exactly the bytes produced by `Packer::pack`,
+// which is the same layout as `eosio::pack` in eosio.cdt.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::serializer::{
+    Packer,
+    Encoder,
+};
+
+/// Writes the packed bytes of `value` to `path`.
+pub fn write_fixture<T: Packer, P: AsRef<Path>>(path: P, value: &T) -> io::Result<()> {
+    fs::write(path, Encoder::pack(value))
+}
+
+/// Reads a fixture written by `write_fixture` or by eosio.cdt and unpacks it into `T`.
+pub fn read_fixture<T: Packer + Default, P: AsRef<Path>>(path: P) -> io::Result<T> {
+    let data = fs::read(path)?;
+    let mut value = T::default();
+    let size = value.unpack(&data);
+    if size != data.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("fixture has {} trailing bytes", data.len() - size)
+        ));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::name::Name;
+    use crate::structs::*;
+
+    #[test]
+    fn test_producer_authority_fixture() {
+        let mut key = ECCPublicKey::default();
+        key.data[0] = 3;
+        let producer = ProducerAuthority {
+            producer_name: Name::new("alice"),
+            authority: BlockSigningAuthority::V0(BlockSigningAuthorityV0 {
+                threshold: 1,
+                keys: vec![KeyWeight{ key: PublicKey::K1(key), weight: 1 }],
+            }),
+        };
+
+        let path = std::env::temp_dir().join("rust_chain_producer_authority.bin");
+        write_fixture(&path, &producer).unwrap();
+        let loaded: ProducerAuthority = read_fixture(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(loaded == producer);
+    }
+}
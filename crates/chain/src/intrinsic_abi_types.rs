@@ -11,6 +11,7 @@ pub use crate::name::{
 
 pub use crate::varint::{
     VarUint32,
+    VarInt32,
 };
 
 pub use crate::structs::{
@@ -21,6 +21,9 @@ fn panic(info: &core::panic::PanicInfo) -> ! {
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+// lets the code generated by `StructPacker` refer to `::rust_chain` inside of this crate
+extern crate self as rust_chain;
+
 use cfg_if::cfg_if;
 
 #[cfg(feature = "std")]
@@ -94,6 +97,7 @@ pub use self::structs:: {
     WebAuthNPublicKey,
     PublicKey,
     Signature,
+    SignatureType,
     Uint128,
     Int128,
     Uint256,
@@ -104,6 +108,8 @@ pub use self::structs:: {
     KeyWeight,
     BlockSigningAuthority,
     ProducerAuthority,
+    ParseHexError,
+    ParseTimeError,
 };
 
 ///
@@ -119,6 +125,7 @@ pub use serializer::{
     Encoder,
     Decoder,
     Packer,
+    DecodeError,
 };
 
 ///
@@ -164,6 +171,9 @@ pub use asset::{
 
 mod privileged;
 
+///
+pub mod system_actions;
+
 ///
 pub mod crypto;
 pub use crypto::{
@@ -258,12 +268,27 @@ pub mod utils;
 pub mod varint;
 pub use varint::{
     VarUint32,
+    VarInt32,
 };
 
 ///
 pub mod binary_extension;
 pub use binary_extension::BinaryExtension;
 
+///
+pub mod versioned;
+pub use versioned::{
+    Versioned,
+    VersionedPayload,
+};
+
+///
+pub mod tagged;
+pub use tagged::{
+    TaggedDecoder,
+    pack_tagged,
+};
+
 ///
 pub mod intrinsic_abi_types;
 pub use intrinsic_abi_types::*;
@@ -278,3 +303,18 @@ pub use eosio_scale_info;
 
 #[cfg(feature = "std")]
 pub mod abi;
+
+#[cfg(feature = "std")]
+pub mod fixtures;
+
+#[cfg(feature = "std")]
+pub mod action_trace;
+
+#[cfg(feature = "std")]
+pub mod table_rows;
+
+#[cfg(feature = "std")]
+pub mod apply_context;
+
+#[cfg(feature = "std")]
+pub mod typed_action;
@@ -14,11 +14,11 @@ use crate::{
 use crate::serializer::{ 
     Packer,
 	Encoder,
+    DecodeError,
 };
 
 use crate::vmapi::eosio::{
     check,
-    eosio_memcpy,
 };
 
 const INVALID_NAME_CHAR: u8 = 0xffu8;
@@ -224,6 +224,14 @@ impl Name {
         Name { n: n }
     }
 
+    /// Like `from_u64`, but returns `None` instead of aborting on an invalid value
+    pub fn try_from_u64(n: u64) -> Option<Self> {
+        if n == INVALID_NAME {
+            return None;
+        }
+        Some(Name { n: n })
+    }
+
     ///
     pub fn from_str(s: &str) -> Self {
 		return Name{ n: str_to_name_checked(s) };
@@ -235,6 +243,12 @@ impl Name {
     }
 }
 
+impl From<Name> for u64 {
+    fn from(name: Name) -> u64 {
+        name.n
+    }
+}
+
 impl Packer for Name {
     fn size(&self) -> usize {
         return 8;
@@ -246,10 +260,13 @@ impl Packer for Name {
 
     fn unpack(&mut self, raw: &[u8]) -> usize {
         check(raw.len() >= 8, "Name.unpack: buffer overflow!");
-        self.n = 0;
-        eosio_memcpy(&self.n as *const u64 as *mut u8, raw.as_ptr(), 8);
+        self.n = u64::from_le_bytes(raw[..8].try_into().unwrap());
         return 8;
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        self.n.try_unpack(raw)
+    }
 }
 
 pub const SAME_PAYER: Name = Name{n: 0};
@@ -268,3 +285,133 @@ macro_rules! name {
         }
     };
 }
+
+/// Declares an enum whose variants are packed as `Name` values,
+/// for ABIs that model enums as `name` fields.
+///
+/// ```ignore
+/// name_enum! {
+///     pub enum Status {
+///         Active = "active",
+///         Frozen = "frozen",
+///     }
+/// }
+/// ```
+///
+/// The first variant is the default value. Unpacking an unknown name fails with `check`.
+#[macro_export]
+macro_rules! name_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $ty:ident {
+            $first:ident = $first_name:literal
+            $(, $variant:ident = $name:literal)* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+        $vis enum $ty {
+            $first,
+            $($variant,)*
+        }
+
+        impl $ty {
+            ///
+            pub fn to_name(&self) -> $crate::name::Name {
+                match self {
+                    $ty::$first => $crate::name::Name{n: $crate::name::static_str_to_name($first_name)},
+                    $($ty::$variant => $crate::name::Name{n: $crate::name::static_str_to_name($name)},)*
+                }
+            }
+
+            ///
+            pub fn from_name(name: $crate::name::Name) -> Option<Self> {
+                if name.n == $crate::name::static_str_to_name($first_name) {
+                    return Some($ty::$first);
+                }
+                $(
+                    if name.n == $crate::name::static_str_to_name($name) {
+                        return Some($ty::$variant);
+                    }
+                )*
+                None
+            }
+        }
+
+        impl Default for $ty {
+            fn default() -> Self {
+                $ty::$first
+            }
+        }
+
+        impl $crate::serializer::Packer for $ty {
+            fn size(&self) -> usize {
+                8
+            }
+
+            fn pack(&self, enc: &mut $crate::serializer::Encoder) -> usize {
+                $crate::serializer::Packer::pack(&self.to_name(), enc)
+            }
+
+            fn unpack(&mut self, data: &[u8]) -> usize {
+                let mut name = $crate::name::Name::default();
+                let size = $crate::serializer::Packer::unpack(&mut name, data);
+                match $ty::from_name(name) {
+                    Some(value) => {
+                        *self = value;
+                    }
+                    None => {
+                        $crate::vmapi::eosio::check(false, concat!(stringify!($ty), ".unpack: unknown name"));
+                    }
+                }
+                size
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serializer::Packer;
+
+    name_enum! {
+        enum Status {
+            Active = "active",
+            Frozen = "frozen",
+            Closed = "closed",
+        }
+    }
+
+    #[test]
+    fn test_name_enum() {
+        for status in [Status::Active, Status::Frozen, Status::Closed] {
+            let packed = Encoder::pack(&status);
+            assert_eq!(packed, Encoder::pack(&status.to_name()));
+
+            let mut unpacked = Status::default();
+            assert_eq!(unpacked.unpack(&packed), 8);
+            assert_eq!(unpacked, status);
+        }
+
+        assert_eq!(Status::Frozen.to_name(), Name::new("frozen"));
+        assert_eq!(Status::from_name(Name::new("closed")), Some(Status::Closed));
+        assert_eq!(Status::from_name(Name::new("unknown")), None);
+    }
+
+    #[test]
+    fn test_name_u64_round_trip() {
+        let name = Name::new("alice.bob");
+        let value = name.value();
+        assert_eq!(value, s2n("alice.bob"));
+        assert_eq!(u64::from(name), value);
+
+        let name = Name::try_from_u64(value).unwrap();
+        assert_eq!(name, Name::from_u64(value));
+        assert_eq!(name.to_string(), "alice.bob");
+        assert_eq!(Name::from_str(&name.to_string()).value(), value);
+
+        assert_eq!(Name::try_from_u64(0).unwrap().to_string(), "");
+        assert_eq!(Name::try_from_u64(INVALID_NAME), None);
+    }
+}
@@ -116,7 +116,8 @@ pub struct BlockchainParameters {
 
 impl Packer for BlockchainParameters {
     fn size(&self) -> usize {
-        return size_of::<BlockchainParameters>();
+        // size_of::<BlockchainParameters>() includes the trailing padding required by align(8)
+        return 8 + 14 * size_of::<u32>() + 2 * size_of::<u16>();
     }
 
     fn pack(&self, enc: &mut Encoder) -> usize {
@@ -55,6 +55,69 @@ pub trait Packer {
     ///
     /// The number of bytes read from the byte array.
     fn unpack(&mut self, data: &[u8]) -> usize;
+
+    /// Unpacks this value like `unpack`, but returns an error on malformed data
+    /// instead of aborting the execution, which is useful to decode untrusted data.
+    ///
+    /// The default implementation delegates to `unpack` and so still aborts on malformed data.
+    /// It is overridden by the integer, `bool`, `String`, `Vec`, `Option`, `Name`, `Symbol`, `Asset`,
+    /// varint, checksum, key and signature types, and by the structs generated by `StructPacker`
+    /// and the contract macro, which are fallible as long as their fields are.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes read from the byte array.
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        Ok(self.unpack(data))
+    }
+}
+
+/// Error returned by `Packer::try_unpack`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DecodeError {
+    /// The data is shorter than the packed value.
+    BufferOverflow,
+    /// The type tag of a variant is unknown.
+    BadVariant {
+        /// name of the variant type
+        name: &'static str,
+        /// the unknown tag
+        tag: u32,
+    },
+    /// Bytes remain after the packed value.
+    TrailingBytes,
+    /// The data is not a valid packed value, e.g. a malformed varint or an invalid utf8 string.
+    Malformed,
+}
+
+/// Packs `value` into a new vector, same as `Encoder::pack`.
+///
+/// ```
+/// use rust_chain::serializer::{pack, unpack};
+///
+/// let data = pack(&1234u32);
+/// assert_eq!(data, vec![210, 4, 0, 0]);
+/// assert_eq!(unpack::<u32>(&data), 1234);
+/// ```
+pub fn pack<T: Packer>(value: &T) -> Vec<u8> {
+    Encoder::pack(value)
+}
+
+/// Unpacks a `T` from the start of `data`, aborting the execution on malformed data.
+/// Use `try_unpack_exact` for untrusted data.
+pub fn unpack<T: Packer + Default>(data: &[u8]) -> T {
+    let mut value = T::default();
+    value.unpack(data);
+    value
+}
+
+/// Unpacks a `T` that must use all of `data`.
+pub fn try_unpack_exact<T: Packer + Default>(data: &[u8]) -> Result<T, DecodeError> {
+    let mut value = T::default();
+    if value.try_unpack(data)? != data.len() {
+        return Err(DecodeError::TrailingBytes);
+    }
+    Ok(value)
 }
 
 /// The `Encoder` struct provides methods for packing values that implement the `Packer` trait.
@@ -88,10 +151,34 @@ impl Encoder {
     ///
     /// A new `Encoder` instance with the given initial capacity.
     pub fn new(size: usize) -> Self {
+        #[cfg(feature = "std")]
+        if let Some(mut buf) = pool::take() {
+            buf.reserve(size);
+            return Self { buf };
+        }
         Self {
             buf: Vec::with_capacity(size)
         }
     }
+
+    /// Same as `new`, an `Encoder` which holds `cap` bytes without reallocating.
+    /// Helpers packing a single value should pass its `Packer::size` as the capacity,
+    /// as `Encoder::pack` does.
+    pub fn with_capacity(cap: usize) -> Self {
+        Self::new(cap)
+    }
+
+    /// Returns the number of bytes the encoder can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    /// Keeps up to `capacity` buffers of the dropped encoders of the current thread,
+    /// which `Encoder::new` reuses instead of allocating. The pool is disabled by default.
+    #[cfg(feature = "std")]
+    pub fn set_pool_capacity(capacity: usize) {
+        pool::set_capacity(capacity);
+    }
     
     /// Returns the packed bytes of this encoder as a byte array.
     ///
@@ -153,6 +240,58 @@ impl Encoder {
 
 }
 
+#[cfg(feature = "std")]
+impl Drop for Encoder {
+    fn drop(&mut self) {
+        pool::put(core::mem::take(&mut self.buf));
+    }
+}
+
+#[cfg(feature = "std")]
+mod pool {
+    use std::cell::RefCell;
+    use crate::vec::Vec;
+
+    /// buffers larger than this are freed instead of kept in the pool
+    const MAX_POOLED_BUFFER_SIZE: usize = 64 * 1024;
+
+    #[derive(Default)]
+    struct EncoderPool {
+        buffers: Vec<Vec<u8>>,
+        capacity: usize,
+    }
+
+    std::thread_local! {
+        static ENCODER_POOL: RefCell<EncoderPool> = RefCell::new(EncoderPool::default());
+    }
+
+    pub(super) fn set_capacity(capacity: usize) {
+        ENCODER_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            pool.capacity = capacity;
+            pool.buffers.truncate(capacity);
+        });
+    }
+
+    pub(super) fn take() -> Option<Vec<u8>> {
+        ENCODER_POOL.try_with(|pool| pool.borrow_mut().buffers.pop()).ok().flatten()
+    }
+
+    pub(super) fn put(mut buf: Vec<u8>) {
+        if buf.capacity() == 0 || buf.capacity() > MAX_POOLED_BUFFER_SIZE {
+            return;
+        }
+        buf.clear();
+        // the pool is gone when encoders are dropped during thread teardown
+        let _ = ENCODER_POOL.try_with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.buffers.len() < pool.capacity {
+                pool.buffers.push(buf);
+            }
+        });
+    }
+}
+
 /// A struct for unpacking packed data
 ///
 /// # Examples
@@ -190,11 +329,42 @@ impl<'a> Decoder<'a> {
         size
     }
 
+    /// Unpacks the given value like `unpack`, but returns an error on malformed data.
+    /// A step that reads no bytes for a value which packs to at least one byte is malformed,
+    /// which stops parsers of untrusted data that would otherwise loop without advancing.
+    pub fn try_unpack<T>(&mut self, packer: &mut T) -> Result<usize, DecodeError>
+    where T: Packer,
+    {
+        let data = self.buf.get(self.pos..).ok_or(DecodeError::BufferOverflow)?;
+        let size = packer.try_unpack(data)?;
+        if size > data.len() {
+            return Err(DecodeError::BufferOverflow);
+        }
+        if size == 0 && packer.size() != 0 {
+            return Err(DecodeError::Malformed);
+        }
+        self.pos += size;
+        Ok(size)
+    }
+
     /// Returns the current position of the decoder
     pub fn get_pos(&self) -> usize {
         self.pos
     }
 
+    /// Returns the number of bytes not unpacked yet
+    pub fn remaining(&self) -> usize {
+        self.buf.len().saturating_sub(self.pos)
+    }
+
+    /// Returns `DecodeError::TrailingBytes` unless all of the data has been unpacked
+    pub fn finish(self) -> Result<(), DecodeError> {
+        if self.remaining() != 0 {
+            return Err(DecodeError::TrailingBytes);
+        }
+        Ok(())
+    }
+
 }
 
 /// A trait for packing and unpacking values
@@ -221,6 +391,14 @@ macro_rules! impl_packed {
                 *self = $ty::from_le_bytes(data[..self.size()].try_into().unwrap());
                 size_of::<$ty>()
             }
+
+            /// Unpacks this value from the given data, returning an error if it is too short.
+            fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+                if data.len() < self.size() {
+                    return Err(DecodeError::BufferOverflow);
+                }
+                Ok(self.unpack(data))
+            }
         }
     };
 }
@@ -254,6 +432,17 @@ impl Packer for bool {
         }
         self.size()
     }
+
+    /// Unpacks this value from the given data, returning an error on malformed data.
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        match data.first() {
+            Some(0) => *self = false,
+            Some(1) => *self = true,
+            Some(_) => return Err(DecodeError::Malformed),
+            None => return Err(DecodeError::BufferOverflow),
+        }
+        Ok(self.size())
+    }
 }
 
 /// Implement `Packer` for i8 type.
@@ -277,6 +466,12 @@ impl Packer for i8 {
         *self = data[0] as i8;
         self.size()
     }
+
+    /// Unpacks this value from the given data, returning an error if it is too short.
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        *self = *data.first().ok_or(DecodeError::BufferOverflow)? as i8;
+        Ok(self.size())
+    }
 }
 
 /// Implement `Packer` for u8 type.
@@ -300,6 +495,12 @@ impl Packer for u8 {
         *self = data[0];
         self.size()
     }
+
+    /// Unpacks this value from the given data, returning an error if it is too short.
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        *self = *data.first().ok_or(DecodeError::BufferOverflow)?;
+        Ok(self.size())
+    }
 }
 
 impl_packed!(i16);
@@ -313,6 +514,48 @@ impl_packed!(u128);
 impl_packed!(f32);
 impl_packed!(f64);
 
+// Only the integer widths of the ABI, 8 to 128 bits, are supported: an ABI type such as a 24 bit
+// integer has no Rust counterpart to implement `Packer` for, so using one fails to compile.
+
+macro_rules! impl_packed_size {
+    ( $ty:ident, $fixed:ident ) => {
+        #[doc = concat!("Packed as a fixed 8 bytes `", stringify!($fixed), "` whatever the pointer width of the platform,")]
+        /// unpacking a value which does not fit aborts the execution.
+        impl Packer for $ty {
+            /// Returns the size of this value in bytes.
+            fn size(&self) -> usize {
+                size_of::<$fixed>()
+            }
+
+            /// Packs this value into the given encoder.
+            fn pack(&self, enc: &mut Encoder) -> usize {
+                (*self as $fixed).pack(enc)
+            }
+
+            /// Unpacks this value from the given data.
+            fn unpack(&mut self, data: &[u8]) -> usize {
+                let mut value: $fixed = 0;
+                let size = value.unpack(data);
+                let converted = $ty::try_from(value);
+                check(converted.is_ok(), concat!(stringify!($ty), ": value out of range"));
+                *self = converted.unwrap_or_default();
+                size
+            }
+
+            /// Unpacks this value from the given data, returning an error if it does not fit.
+            fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+                let mut value: $fixed = 0;
+                let size = value.try_unpack(data)?;
+                *self = $ty::try_from(value).map_err(|_| DecodeError::Malformed)?;
+                Ok(size)
+            }
+        }
+    };
+}
+
+impl_packed_size!(usize, u64);
+impl_packed_size!(isize, i64);
+
 /// Implement `Packer` for `String` type.
 impl Packer for String {
 
@@ -347,6 +590,17 @@ impl Packer for String {
         }
         size + length.value() as usize
     }
+
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut length = VarUint32{n: 0};
+        let size = length.try_unpack(data)?;
+        let end = size + length.value() as usize;
+        if data.len() < end {
+            return Err(DecodeError::BufferOverflow);
+        }
+        *self = String::from_utf8(data[size..end].to_vec()).map_err(|_| DecodeError::Malformed)?;
+        Ok(end)
+    }
 }
 
 /// Implement `Packer` for `Vec<T>` type.
@@ -361,7 +615,7 @@ impl<T> Packer for Vec<T> where T: Packer + Default {
         for i in 0..self.len() {
             size += self[i].size();
         }
-        VarUint32::new(size as u32).size() + size
+        VarUint32::new(self.len() as u32).size() + size
     }
 
     /// Packs this value into the given encoder.
@@ -388,6 +642,21 @@ impl<T> Packer for Vec<T> where T: Packer + Default {
         }
         dec.get_pos()
     }
+
+    /// Unpacks this value from the given data, returning an error on malformed data.
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut dec = Decoder::new(data);
+        let mut size = VarUint32{n: 0};
+        dec.try_unpack(&mut size)?;
+        // the length is untrusted, don't reserve more elements than there are bytes left
+        self.reserve(core::cmp::min(size.value() as usize, data.len()));
+        for _ in 0..size.value() {
+            let mut v: T = Default::default();
+            dec.try_unpack(&mut v)?;
+            self.push(v);
+        }
+        Ok(dec.get_pos())
+    }
 }
 
 /// Implement `Packer` for `Option<T>` type.
@@ -433,4 +702,326 @@ impl<T> Packer for Option<T> where T: Packer + Default {
         *self = Some(value);
         dec.get_pos()
     }
-}
\ No newline at end of file
+
+    /// Unpacks this value from the given data, returning an error on malformed data.
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut dec = Decoder::new(data);
+        let mut ty: u8 = 0;
+        dec.try_unpack(&mut ty)?;
+        match ty {
+            0 => *self = None,
+            1 => {
+                let mut value: T = Default::default();
+                dec.try_unpack(&mut value)?;
+                *self = Some(value);
+            }
+            _ => return Err(DecodeError::Malformed),
+        }
+        Ok(dec.get_pos())
+    }
+}
+
+/// Implement `Packer` for `HashMap<K, V>` type, packed as an ABI `pair<K, V>[]`
+/// sorted by key, the order of a `std::map` in C++ contracts.
+#[cfg(feature = "std")]
+impl<K, V> Packer for std::collections::HashMap<K, V>
+where
+    K: Packer + Default + Ord + std::hash::Hash,
+    V: Packer + Default,
+{
+    /// Returns the size of this value in bytes.
+    fn size(&self) -> usize {
+        let mut size = VarUint32::new(self.len() as u32).size();
+        for (k, v) in self {
+            size += k.size() + v.size();
+        }
+        size
+    }
+
+    /// Packs this value into the given encoder.
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        let pos = enc.get_size();
+        let len = VarUint32{n: self.len() as u32};
+        len.pack(enc);
+        let mut entries: Vec<(&K, &V)> = self.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        for (k, v) in entries {
+            k.pack(enc);
+            v.pack(enc);
+        }
+        enc.get_size() - pos
+    }
+
+    /// Unpacks this value from the given data, replacing the current entries.
+    fn unpack(&mut self, data: &[u8]) -> usize {
+        let mut dec = Decoder::new(data);
+        let mut size = VarUint32{n: 0};
+        dec.unpack(&mut size);
+        self.clear();
+        self.reserve(size.value() as usize);
+        for _ in 0..size.value() {
+            let mut k: K = Default::default();
+            let mut v: V = Default::default();
+            dec.unpack(&mut k);
+            dec.unpack(&mut v);
+            self.insert(k, v);
+        }
+        dec.get_pos()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::Checksum256;
+
+    /// A variant which, like a careless parser, reads nothing on an unknown tag
+    #[derive(Default)]
+    struct Lenient {
+        tag: u8,
+    }
+
+    impl Packer for Lenient {
+        fn size(&self) -> usize {
+            1
+        }
+
+        fn pack(&self, enc: &mut Encoder) -> usize {
+            self.tag.pack(enc)
+        }
+
+        fn unpack(&mut self, data: &[u8]) -> usize {
+            match data.first() {
+                Some(tag) if *tag < 2 => {
+                    self.tag = *tag;
+                    1
+                }
+                _ => 0,
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoder_with_capacity() {
+        let value = vec![String::from("hello"); 100];
+        let mut enc = Encoder::with_capacity(value.size());
+        let capacity = enc.capacity();
+        assert!(capacity >= value.size());
+        value.pack(&mut enc);
+        assert_eq!(enc.get_size(), value.size());
+        // no reallocation
+        assert_eq!(enc.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let value = (String::from("hello"), 7u64);
+        let data = pack(&value.0);
+        assert_eq!(data, [5, b'h', b'e', b'l', b'l', b'o']);
+        assert_eq!(unpack::<String>(&data), value.0);
+        assert_eq!(unpack::<u64>(&pack(&value.1)), value.1);
+        assert_eq!(unpack::<Vec<u16>>(&pack(&vec![1u16, 2])), [1, 2]);
+    }
+
+    #[test]
+    fn test_usize_pack() {
+        assert_eq!(Encoder::pack(&1usize), [1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(Encoder::pack(&-1isize), [0xff; 8]);
+        assert_eq!(0usize.size(), 8);
+
+        let mut value = 0usize;
+        assert_eq!(value.unpack(&Encoder::pack(&(u32::MAX as usize))), 8);
+        assert_eq!(value, u32::MAX as usize);
+
+        let mut value = 0isize;
+        assert_eq!(value.try_unpack(&[0u8; 4]), Err(DecodeError::BufferOverflow));
+        assert_eq!(value.try_unpack(&Encoder::pack(&(i32::MIN as i64))), Ok(8));
+        assert_eq!(value, i32::MIN as isize);
+
+        // out of range on 32 bit platforms only
+        let mut value = 0usize;
+        let ret = value.try_unpack(&Encoder::pack(&u64::MAX));
+        if size_of::<usize>() == 8 {
+            assert_eq!(ret, Ok(8));
+            assert_eq!(value, usize::MAX);
+        } else {
+            assert_eq!(ret, Err(DecodeError::Malformed));
+        }
+    }
+
+    #[test]
+    fn test_decoder_no_progress() {
+        let mut v: Vec<Lenient> = Vec::new();
+        assert_eq!(v.try_unpack(&[2, 0, 1]), Ok(3));
+        assert_eq!(v.len(), 2);
+
+        // 0xffffffff elements, none of which advances
+        let data = [0xff, 0xff, 0xff, 0xff, 0x0f, 7];
+        let mut v: Vec<Lenient> = Vec::new();
+        assert_eq!(v.try_unpack(&data), Err(DecodeError::Malformed));
+
+        let mut dec = Decoder::new(&data[5..]);
+        let mut value = Lenient::default();
+        assert_eq!(dec.try_unpack(&mut value), Err(DecodeError::Malformed));
+        assert_eq!(dec.get_pos(), 0);
+
+        let mut v: Vec<u64> = Vec::new();
+        assert_eq!(v.try_unpack(&data), Err(DecodeError::BufferOverflow));
+    }
+
+    #[test]
+    fn test_encoder_pool() {
+        Encoder::set_pool_capacity(2);
+        let ptr = {
+            let mut enc = Encoder::new(16);
+            1u64.pack(&mut enc);
+            enc.get_bytes().as_ptr()
+        };
+
+        // the buffer of the dropped encoder is reused, and empty
+        let mut enc = Encoder::new(8);
+        assert_eq!(enc.get_size(), 0);
+        assert_eq!(enc.get_bytes().as_ptr(), ptr);
+        2u32.pack(&mut enc);
+        assert_eq!(enc.get_bytes(), [2, 0, 0, 0]);
+        drop(enc);
+
+        Encoder::set_pool_capacity(0);
+        assert!(pool::take().is_none());
+        assert_eq!(Encoder::pack(&3u16), [3, 0]);
+    }
+
+    // Packs 100k small structs with and without the pool, run with
+    // `cargo test --release -p rust-chain bench_encoder_pool -- --ignored --nocapture`.
+    // The gain depends on the allocator, it is largest with the system allocator
+    // and small with allocators that already cache small blocks per thread.
+    // No numbers are recorded here, they must be measured with the crate itself on the target setup.
+    #[test]
+    #[ignore]
+    fn bench_encoder_pool() {
+        use std::time::Instant;
+
+        let values: Vec<(u64, String)> = (0..100_000u64).map(|i| (i, "hello".into())).collect();
+        let bench = |name: &str| {
+            let start = Instant::now();
+            let mut total = 0;
+            for (n, s) in &values {
+                let mut enc = Encoder::new(n.size() + s.size());
+                n.pack(&mut enc);
+                s.pack(&mut enc);
+                total += enc.get_size();
+            }
+            println!("{}: {:?} for {} bytes", name, start.elapsed(), total);
+        };
+
+        Encoder::set_pool_capacity(0);
+        bench("without pool");
+        Encoder::set_pool_capacity(16);
+        bench("with pool");
+        Encoder::set_pool_capacity(0);
+    }
+
+    #[test]
+    fn test_nested_vec_pack_unpack() {
+        let empty: Vec<Vec<u8>> = Vec::new();
+        let packed = Encoder::pack(&empty);
+        assert_eq!(packed, vec![0]);
+        assert_eq!(empty.size(), packed.len());
+
+        let empty_inner: Vec<Vec<u8>> = vec![Vec::new(), Vec::new()];
+        let packed = Encoder::pack(&empty_inner);
+        assert_eq!(packed, vec![2, 0, 0]);
+        let mut unpacked: Vec<Vec<u8>> = Vec::new();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert_eq!(unpacked, empty_inner);
+
+        let bytes: Vec<Vec<u8>> = vec![vec![1, 2, 3], vec![], vec![4]];
+        let packed = Encoder::pack(&bytes);
+        assert_eq!(packed, vec![3, 3, 1, 2, 3, 0, 1, 4]);
+        assert_eq!(bytes.size(), packed.len());
+        let mut unpacked: Vec<Vec<u8>> = Vec::new();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert_eq!(unpacked, bytes);
+
+        let mut hash = Checksum256::default();
+        hash.data[0] = 0xab;
+        let hashes: Vec<Vec<Checksum256>> = vec![vec![hash, Checksum256::default()], vec![hash]];
+        let packed = Encoder::pack(&hashes);
+        assert_eq!(packed.len(), 1 + 1 + 32 * 2 + 1 + 32);
+        assert_eq!(hashes.size(), packed.len());
+        assert_eq!(packed[0], 2);
+        assert_eq!(packed[1], 2);
+        assert_eq!(packed[2], 0xab);
+        assert_eq!(packed[66], 1);
+        let mut unpacked: Vec<Vec<Checksum256>> = Vec::new();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert!(unpacked == hashes);
+    }
+
+    #[test]
+    fn test_integer_pack_little_endian() {
+        assert_eq!(Encoder::pack(&0x0102u16), vec![0x02, 0x01]);
+        assert_eq!(Encoder::pack(&0x01020304u32), vec![0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(Encoder::pack(&-2i32), vec![0xfe, 0xff, 0xff, 0xff]);
+        assert_eq!(Encoder::pack(&0x0102030405060708u64), vec![8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(Encoder::pack(&1u128)[0], 1);
+        assert_eq!(Encoder::pack(&1.0f64), vec![0, 0, 0, 0, 0, 0, 0xf0, 0x3f]);
+
+        let mut value = 0u64;
+        value.unpack(&[8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(value, 0x0102030405060708);
+
+        let name = crate::name::Name::from_u64(0x0102030405060708);
+        let packed = Encoder::pack(&name);
+        assert_eq!(packed, vec![8, 7, 6, 5, 4, 3, 2, 1]);
+        let mut unpacked = crate::name::Name::default();
+        unpacked.unpack(&packed);
+        assert_eq!(unpacked, name);
+    }
+
+    #[test]
+    fn test_std_collections_pack() {
+        let values: std::vec::Vec<u64> = std::vec![1, 2];
+        let mut expected = vec![2u8];
+        expected.extend_from_slice(&1u64.to_le_bytes());
+        expected.extend_from_slice(&2u64.to_le_bytes());
+        assert_eq!(Encoder::pack(&values), expected);
+
+        let s = std::string::String::from("abc");
+        assert_eq!(Encoder::pack(&s), vec![3, b'a', b'b', b'c']);
+
+        let mut map: std::collections::HashMap<u64, String> = std::collections::HashMap::new();
+        map.insert(1, "a".into());
+        let packed = Encoder::pack(&map);
+        assert_eq!(packed, vec![1, 1, 0, 0, 0, 0, 0, 0, 0, 1, b'a']);
+        assert_eq!(map.size(), packed.len());
+
+        map.insert(2, "bc".into());
+        let packed = Encoder::pack(&map);
+        let mut unpacked: std::collections::HashMap<u64, String> = std::collections::HashMap::new();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert_eq!(unpacked, map);
+    }
+
+    #[test]
+    fn test_hash_map_sorted_by_key() {
+        let mut map: std::collections::HashMap<u64, String> = std::collections::HashMap::new();
+        for (k, v) in [(300u64, "c"), (2, "a"), (0x0100000000000000, "d"), (17, "b")] {
+            map.insert(k, v.into());
+        }
+
+        let mut expected = vec![4u8];
+        for (k, v) in [(2u64, b'a'), (17, b'b'), (300, b'c'), (0x0100000000000000, b'd')] {
+            expected.extend_from_slice(&k.to_le_bytes());
+            expected.extend_from_slice(&[1, v]);
+        }
+        assert_eq!(Encoder::pack(&map), expected);
+        assert_eq!(map.size(), expected.len());
+
+        // the packed entries replace the existing ones
+        let mut unpacked: std::collections::HashMap<u64, String> = std::collections::HashMap::new();
+        unpacked.insert(5, "stale".into());
+        assert_eq!(unpacked.unpack(&expected), expected.len());
+        assert_eq!(unpacked, map);
+    }
+}
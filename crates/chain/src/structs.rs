@@ -1,3 +1,6 @@
+use core::ops;
+use core::time::Duration;
+
 use crate::utils::{
     decode_hex,
 };
@@ -11,14 +14,16 @@ use crate::name::{ Name };
 
 use crate::print::{ 
     Printable,
+    prints,
     printui128,
-    // printhex,
+    printhex,
 };
 
 use crate::serializer::{
     Packer,
     Encoder,
     Decoder,
+    DecodeError,
 };
 
 use crate::varint::{
@@ -72,6 +77,76 @@ impl Packer for Float128 {
     }
 }
 
+/// Error returned when parsing a checksum from a hex string
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParseHexError {
+    ///
+    BadLength,
+    ///
+    BadHex,
+}
+
+/// Decodes the hex string `s` into `data`, which must be exactly filled
+fn parse_hex_bytes(s: &str, data: &mut [u8]) -> Result<(), ParseHexError> {
+    if s.len() != data.len() * 2 {
+        return Err(ParseHexError::BadLength);
+    }
+
+    if !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ParseHexError::BadHex);
+    }
+
+    for (i, b) in data.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| ParseHexError::BadHex)?;
+    }
+    Ok(())
+}
+
+fn check_hex_bytes(ret: Result<(), ParseHexError>, bad_length_msg: &str) {
+    match ret {
+        Ok(()) => {}
+        Err(ParseHexError::BadLength) => check(false, bad_length_msg),
+        Err(ParseHexError::BadHex) => check(false, "bad hex characters"),
+    }
+}
+
+/// Implements `Display` as a lowercase hex string and `FromStr` from a hex string
+macro_rules! impl_checksum_hex {
+    ($ty:ty) => {
+        impl core::fmt::Display for $ty {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                for b in self.data.iter() {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl core::str::FromStr for $ty {
+            type Err = ParseHexError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut ret = Self::default();
+                parse_hex_bytes(s, &mut ret.data)?;
+                Ok(ret)
+            }
+        }
+    };
+}
+
+impl_checksum_hex!(Checksum160);
+impl_checksum_hex!(Checksum256);
+impl_checksum_hex!(Checksum512);
+
+fn try_unpack_bytes(data: &mut [u8], raw: &[u8]) -> Result<usize, DecodeError> {
+    let size = data.len();
+    if raw.len() < size {
+        return Err(DecodeError::BufferOverflow);
+    }
+    slice_copy(data, &raw[..size]);
+    Ok(size)
+}
+
 ///
 #[repr(C, align(8))]
 #[cfg_attr(feature = "std", derive(eosio_scale_info::TypeInfo))]
@@ -83,10 +158,8 @@ pub struct Checksum160 {
 impl Checksum160 {
     ///
     pub fn from_hex(s: &str) -> Self {
-        check(s.len() == 40, "Checksum160: bad hex string length");
-        let data = decode_hex(s);
         let mut ret = Self::default();
-        slice_copy(&mut ret.data, &data);
+        check_hex_bytes(parse_hex_bytes(s, &mut ret.data), "Checksum160: bad hex string length");
         return ret;
     }
 }
@@ -108,6 +181,10 @@ impl Packer for Checksum160 {
         slice_copy(&mut self.data, &raw[..size]);
         return size;
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        try_unpack_bytes(&mut self.data, raw)
+    }
 }
 
 ///
@@ -122,12 +199,25 @@ pub struct Checksum256 {
 impl Checksum256 {
     ///
     pub fn from_hex(s: &str) -> Self {
-        check(s.len() == 64, "Checksum256: bad hex string length");
-        let data = decode_hex(s);
         let mut ret = Self::default();
-        slice_copy(&mut ret.data, &data);
+        check_hex_bytes(parse_hex_bytes(s, &mut ret.data), "Checksum256: bad hex string length");
         return ret;
     }
+
+    /// Packs `parts` in order and returns the sha256 hash of the packed data,
+    /// e.g. `Checksum256::from_parts(&[&owner, &id])` for `sha256(owner || id)`.
+    pub fn from_parts(parts: &[&dyn Packer]) -> Self {
+        let mut size: usize = 0;
+        for part in parts {
+            size += part.size();
+        }
+
+        let mut enc = Encoder::new(size);
+        for part in parts {
+            part.pack(&mut enc);
+        }
+        crate::crypto::sha256(enc.get_bytes())
+    }
 }
 
 impl Packer for Checksum256 {
@@ -147,6 +237,10 @@ impl Packer for Checksum256 {
         slice_copy(&mut self.data, &raw[..size]);
         return self.size();
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        try_unpack_bytes(&mut self.data, raw)
+    }
 }
 
 ///
@@ -160,10 +254,8 @@ pub struct Checksum512 {
 impl Checksum512 {
     ///
     pub fn from_hex(s: &str) -> Self {
-        check(s.len() == 128, "Checksum512: bad hex string length");
-        let data = decode_hex(s);
         let mut ret = Self::default();
-        slice_copy(&mut ret.data, &data);
+        check_hex_bytes(parse_hex_bytes(s, &mut ret.data), "Checksum512: bad hex string length");
         return ret;
     }
 }
@@ -193,6 +285,10 @@ impl Packer for Checksum512 {
         slice_copy(&mut self.data, &raw[..size]);
         return size;
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        try_unpack_bytes(&mut self.data, raw)
+    }
 }
 
 
@@ -213,6 +309,52 @@ impl ECCPublicKey {
         slice_copy(&mut ret.data, &data);
         return ret;
     }
+
+    /// Creates a key from its 33 bytes compressed form
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let mut ret = Self::default();
+        check(data.len() == 33, "ECCPublicKey: bad data length");
+        slice_copy(&mut ret.data, data);
+        return ret;
+    }
+
+    ///
+    pub fn as_bytes(&self) -> &[u8; 33] {
+        &self.data
+    }
+}
+
+#[cfg(feature = "crypto")]
+impl ECCPublicKey {
+    /// Returns the 65 bytes uncompressed SEC1 form (`0x04 || x || y`) of this key
+    pub fn to_uncompressed(&self) -> [u8; 65] {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+        let mut ret = [0u8; 65];
+        match k256::PublicKey::from_sec1_bytes(&self.data) {
+            Ok(key) => {
+                slice_copy(&mut ret, key.to_encoded_point(false).as_bytes());
+            }
+            Err(_) => {
+                check(false, "ECCPublicKey: invalid public key");
+            }
+        }
+        return ret;
+    }
+
+    /// Converts a 65 bytes uncompressed SEC1 key to the compressed form,
+    /// returns `None` if `data` is not a point on the secp256k1 curve.
+    pub fn from_uncompressed(data: &[u8; 65]) -> Option<Self> {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+        if data[0] != 0x04 {
+            return None;
+        }
+        let key = k256::PublicKey::from_sec1_bytes(data).ok()?;
+        let mut ret = Self::default();
+        slice_copy(&mut ret.data, key.to_encoded_point(true).as_bytes());
+        Some(ret)
+    }
 }
 
 impl Default for ECCPublicKey {
@@ -240,6 +382,10 @@ impl Packer for ECCPublicKey {
         slice_copy(&mut self.data, &raw[..size]);
         return size;
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        try_unpack_bytes(&mut self.data, raw)
+    }
 }
 
 ///
@@ -290,6 +436,18 @@ impl Packer for UserPresence {
         }
         return 1;
     }
+
+    ///
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        match data.first() {
+            None => return Err(DecodeError::BufferOverflow),
+            Some(0) => *self = UserPresence::None,
+            Some(1) => *self = UserPresence::Present,
+            Some(2) => *self = UserPresence::Verified,
+            Some(tag) => return Err(DecodeError::BadVariant { name: "UserPresence", tag: *tag as u32 }),
+        }
+        Ok(1)
+    }
 }
 
 ///
@@ -309,6 +467,29 @@ impl WebAuthNPublicKey {
     pub fn new(key: ECCPublicKey, user_presence: UserPresence, rpid: String) -> Self {
         Self { key, user_presence, rpid }
     }
+
+    /// Checks that `rpid` is an ASCII domain name, e.g. `example.com`
+    pub fn is_valid_rpid(&self) -> bool {
+        is_valid_domain(&self.rpid)
+    }
+}
+
+/// Maximum size of a WebAuthn rpid, which is the maximum length of a DNS name
+pub const MAX_RPID_SIZE: usize = 253;
+
+fn is_valid_domain(s: &str) -> bool {
+    if s.is_empty() || s.len() > MAX_RPID_SIZE {
+        return false;
+    }
+
+    s.split('.').all(|label| {
+        let raw = label.as_bytes();
+        !raw.is_empty()
+            && raw.len() <= 63
+            && raw[0] != b'-'
+            && raw[raw.len() - 1] != b'-'
+            && raw.iter().all(|c| c.is_ascii_alphanumeric() || *c == b'-')
+    })
 }
 
 impl Packer for WebAuthNPublicKey {
@@ -321,6 +502,7 @@ impl Packer for WebAuthNPublicKey {
     fn pack(&self, enc: &mut Encoder) -> usize {
         let pos = enc.get_size();
         
+        check(self.rpid.len() <= MAX_RPID_SIZE, "WebAuthNPublicKey: rpid is too long");
         self.key.pack(enc);
         self.user_presence.pack(enc);
         self.rpid.pack(enc);
@@ -335,8 +517,20 @@ impl Packer for WebAuthNPublicKey {
         dec.unpack(&mut self.key);
         dec.unpack(&mut self.user_presence);
         dec.unpack(&mut self.rpid);
+        check(self.rpid.len() <= MAX_RPID_SIZE, "WebAuthNPublicKey: rpid is too long");
         return dec.get_pos();
     }
+
+    ///
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut pos = self.key.try_unpack(data)?;
+        pos += self.user_presence.try_unpack(&data[pos..])?;
+        pos += self.rpid.try_unpack(&data[pos..])?;
+        if self.rpid.len() > MAX_RPID_SIZE {
+            return Err(DecodeError::Malformed);
+        }
+        Ok(pos)
+    }
 }
 
 ///
@@ -351,6 +545,18 @@ pub enum PublicKey {
     WebAuth(WebAuthNPublicKey),
 }
 
+impl PublicKey {
+    ///
+    pub fn from_k1_bytes(data: &[u8]) -> Self {
+        PublicKey::K1(ECCPublicKey::from_bytes(data))
+    }
+
+    ///
+    pub fn from_r1_bytes(data: &[u8]) -> Self {
+        PublicKey::R1(ECCPublicKey::from_bytes(data))
+    }
+}
+
 impl Default for PublicKey {
     ///
     #[inline]
@@ -415,6 +621,26 @@ impl Packer for PublicKey {
         }
         return dec.get_pos();
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        let ty = *raw.first().ok_or(DecodeError::BufferOverflow)?;
+        let size = match ty {
+            0 | 1 => {
+                let mut pub_key = ECCPublicKey::default();
+                let size = pub_key.try_unpack(&raw[1..])?;
+                *self = if ty == 0 { PublicKey::K1(pub_key) } else { PublicKey::R1(pub_key) };
+                size
+            }
+            2 => {
+                let mut pub_key = WebAuthNPublicKey::default();
+                let size = pub_key.try_unpack(&raw[1..])?;
+                *self = PublicKey::WebAuth(pub_key);
+                size
+            }
+            _ => return Err(DecodeError::BadVariant { name: "PublicKey", tag: ty as u32 }),
+        };
+        Ok(1 + size)
+    }
 }
 
 ///
@@ -427,15 +653,100 @@ pub struct Signature {
 	data: [u8; 65],
 }
 
+/// Type of a K1 signature
+pub const SIGNATURE_TYPE_K1: u8 = 0;
+/// Type of a R1 signature
+pub const SIGNATURE_TYPE_R1: u8 = 1;
+/// Type of a WebAuthn signature
+pub const SIGNATURE_TYPE_WEBAUTHN: u8 = 2;
+
+/// Curve or scheme of a signature, as encoded in its first byte
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SignatureType {
+    ///
+    K1,
+    ///
+    R1,
+    /// variable-length signature, which `Signature` can't hold
+    WebAuthn,
+}
+
 impl Signature {
+    /// Creates a K1 or R1 signature from its 65 bytes of data
+    pub fn new(ty: u8, data: [u8; 65]) -> Self {
+        check(ty == SIGNATURE_TYPE_K1 || ty == SIGNATURE_TYPE_R1, "bad signature type");
+        Self { ty, data }
+    }
+
+    ///
+    pub fn ty(&self) -> u8 {
+        self.ty
+    }
+
+    ///
+    pub fn signature_type(&self) -> SignatureType {
+        match self.ty {
+            SIGNATURE_TYPE_K1 => SignatureType::K1,
+            SIGNATURE_TYPE_R1 => SignatureType::R1,
+            _ => SignatureType::WebAuthn,
+        }
+    }
+
     ///
     pub fn from_hex(s: &str) -> Self {
-        let mut ret = Self::default();
+        Self::from_hex_with_type(SIGNATURE_TYPE_K1, s)
+    }
+
+    /// Creates a K1 or R1 signature from the hex string of its 65 bytes of data
+    pub fn from_hex_with_type(ty: u8, s: &str) -> Self {
         check(s.len() == 65*2, "Signature: bad hex string length");
-        let data = decode_hex(s);
-        ret.ty = 0;
-        slice_copy(&mut ret.data, &data);
-        return ret;
+        let mut data = [0u8; 65];
+        slice_copy(&mut data, &decode_hex(s));
+        Self::new(ty, data)
+    }
+}
+
+/// order of the secp256k1 curve
+#[cfg(feature = "crypto")]
+const SECP256K1_ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+/// half order of the secp256k1 curve
+#[cfg(feature = "crypto")]
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+#[cfg(feature = "crypto")]
+impl Signature {
+    /// Checks that the S component is not greater than half of the curve order (low-S).
+    /// The signature data is laid out as `recovery id || r || s`.
+    pub fn is_canonical(&self) -> bool {
+        self.data[33..] <= SECP256K1_HALF_ORDER[..]
+    }
+
+    /// Returns the malleated signature `(r, n - s)` with the flipped recovery id,
+    /// which is valid for the same digest and key but not canonical if `self` is.
+    /// The header is always the compressed one, `27 + 4 + recovery id`.
+    pub fn to_high_s(&self) -> Self {
+        let mut ret = self.clone();
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let mut v = SECP256K1_ORDER[i] as i16 - self.data[33 + i] as i16 - borrow;
+            borrow = 0;
+            if v < 0 {
+                v += 256;
+                borrow = 1;
+            }
+            ret.data[33 + i] = v as u8;
+        }
+
+        let recovery_id = (self.data[0].wrapping_sub(27)) & 3;
+        ret.data[0] = 27 + 4 + (recovery_id ^ 1);
+        ret
     }
 }
 
@@ -464,10 +775,22 @@ impl Packer for Signature {
         let size = self.size();
         check(data.len() >= size, "Signature::unpack: buffer overflow");
         self.ty = data[0];
-        check(self.ty == 0, "bad signature type");
+        // K1 and R1 signatures share the same layout, WebAuthn signatures are variable-length
+        check(self.ty != SIGNATURE_TYPE_WEBAUTHN, "WebAuthn signatures are not supported");
+        check(self.ty == SIGNATURE_TYPE_K1 || self.ty == SIGNATURE_TYPE_R1, "bad signature type");
         slice_copy(&mut self.data, &data[1..size]);
         return self.size();
     }
+
+    ///
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let ty = *data.first().ok_or(DecodeError::BufferOverflow)?;
+        if ty != SIGNATURE_TYPE_K1 && ty != SIGNATURE_TYPE_R1 {
+            return Err(DecodeError::BadVariant { name: "Signature", tag: ty as u32 });
+        }
+        self.ty = ty;
+        Ok(1 + try_unpack_bytes(&mut self.data, &data[1..])?)
+    }
 }
 
 ///
@@ -492,17 +815,186 @@ impl Default for Uint128 {
     }
 }
 
+impl From<u128> for Uint128 {
+    fn from(value: u128) -> Self {
+        Self {
+            lo: value as u64,
+            hi: (value >> 64) as u64,
+        }
+    }
+}
+
+impl From<Uint128> for u128 {
+    fn from(value: Uint128) -> Self {
+        (value.hi as u128) << 64 | value.lo as u128
+    }
+}
+
+impl Uint128 {
+    ///
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        u128::from(self).checked_add(rhs.into()).map(Self::from)
+    }
+
+    ///
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        u128::from(self).checked_sub(rhs.into()).map(Self::from)
+    }
+
+    ///
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        u128::from(self).checked_mul(rhs.into()).map(Self::from)
+    }
+
+    /// Returns `None` if `rhs` is zero
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        u128::from(self).checked_div(rhs.into()).map(Self::from)
+    }
+
+    ///
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let (value, overflow) = u128::from(self).overflowing_add(rhs.into());
+        (value.into(), overflow)
+    }
+
+    ///
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let (value, overflow) = u128::from(self).overflowing_sub(rhs.into());
+        (value.into(), overflow)
+    }
+
+    ///
+    pub fn overflowing_mul(self, rhs: Self) -> (Self, bool) {
+        let (value, overflow) = u128::from(self).overflowing_mul(rhs.into());
+        (value.into(), overflow)
+    }
+}
+
+impl ops::Add for Uint128 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let ret = self.checked_add(rhs);
+        check(ret.is_some(), "Uint128: addition overflow");
+        ret.unwrap_or_default()
+    }
+}
+
+impl ops::Sub for Uint128 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let ret = self.checked_sub(rhs);
+        check(ret.is_some(), "Uint128: subtraction underflow");
+        ret.unwrap_or_default()
+    }
+}
+
+impl ops::Mul for Uint128 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let ret = self.checked_mul(rhs);
+        check(ret.is_some(), "Uint128: multiplication overflow");
+        ret.unwrap_or_default()
+    }
+}
+
+impl ops::Div for Uint128 {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        let ret = self.checked_div(rhs);
+        check(ret.is_some(), "Uint128: division by zero");
+        ret.unwrap_or_default()
+    }
+}
+
 ///
 #[repr(C, align(8))]
 #[cfg_attr(feature = "std", derive(eosio_scale_info::TypeInfo))]
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
 pub struct Int128 {
     ///
     pub lo: u64,
-    ///
+    /// high 64 bits, the sign is carried by the top bit
     pub hi: u64,
 }
 
+impl From<i128> for Int128 {
+    fn from(value: i128) -> Self {
+        Self {
+            lo: value as u64,
+            hi: (value >> 64) as u64,
+        }
+    }
+}
+
+impl From<Int128> for i128 {
+    fn from(value: Int128) -> Self {
+        ((value.hi as i128) << 64) | value.lo as i128
+    }
+}
+
+impl PartialOrd for Int128 {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Int128 {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        (self.hi as i64, self.lo).cmp(&(other.hi as i64, other.lo))
+    }
+}
+
+impl Int128 {
+    ///
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        i128::from(self).checked_add(rhs.into()).map(Self::from)
+    }
+
+    ///
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        i128::from(self).checked_sub(rhs.into()).map(Self::from)
+    }
+
+    /// Returns `None` for `i128::MIN`, which has no positive counterpart
+    pub fn checked_neg(self) -> Option<Self> {
+        i128::from(self).checked_neg().map(Self::from)
+    }
+}
+
+impl ops::Add for Int128 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let ret = self.checked_add(rhs);
+        check(ret.is_some(), "Int128: addition overflow");
+        ret.unwrap_or_default()
+    }
+}
+
+impl ops::Sub for Int128 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        let ret = self.checked_sub(rhs);
+        check(ret.is_some(), "Int128: subtraction overflow");
+        ret.unwrap_or_default()
+    }
+}
+
+impl ops::Neg for Int128 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let ret = self.checked_neg();
+        check(ret.is_some(), "Int128: negation overflow");
+        ret.unwrap_or_default()
+    }
+}
+
 ///
 #[repr(C, align(8))]
 #[cfg_attr(feature = "std", derive(eosio_scale_info::TypeInfo))]
@@ -551,7 +1043,55 @@ impl Printable for Uint256 {
         if self.data[0] == 0 {
             printui128(self.data[1]);
         } else {
-            crate::vmapi::print::printhex(self.swap().data.as_ptr() as *mut u8, 32);
+            let mut data = [0u8; 32];
+            data[..16].copy_from_slice(&self.data[1].to_le_bytes());
+            data[16..].copy_from_slice(&self.data[0].to_le_bytes());
+            printhex(&data);
+        }
+    }
+}
+
+impl Printable for Checksum160 {
+    fn print(&self) {
+        printhex(&self.data);
+    }
+}
+
+impl Printable for Checksum256 {
+    fn print(&self) {
+        printhex(&self.data);
+    }
+}
+
+impl Printable for Checksum512 {
+    fn print(&self) {
+        printhex(&self.data);
+    }
+}
+
+impl Printable for ECCPublicKey {
+    fn print(&self) {
+        printhex(&self.data);
+    }
+}
+
+/// Prints `K1:`, `R1:` or `WA:` followed by the key in hex,
+/// the packed `WebAuthNPublicKey` for `WA:`.
+impl Printable for PublicKey {
+    fn print(&self) {
+        match self {
+            PublicKey::K1(x) => {
+                prints("K1:");
+                x.print();
+            }
+            PublicKey::R1(x) => {
+                prints("R1:");
+                x.print();
+            }
+            PublicKey::WebAuth(x) => {
+                prints("WA:");
+                printhex(&Encoder::pack(x));
+            }
         }
     }
 }
@@ -568,12 +1108,61 @@ impl Default for Uint256 {
 
 ///
 #[cfg_attr(feature = "std", derive(eosio_scale_info::TypeInfo))]
-#[derive(Copy, Clone, Default, PartialEq)]
+#[derive(Copy, Clone, Default, Eq, PartialEq, PartialOrd, Ord)]
 pub struct TimePoint {
     /// elapsed in microseconds
     pub elapsed: u64,
 }
 
+impl TimePoint {
+    /// Returns the time elapsed since the Unix epoch as a `Duration`
+    pub fn to_duration(&self) -> Duration {
+        Duration::from_micros(self.elapsed)
+    }
+
+    ///
+    pub fn from_sec(sec: TimePointSec) -> Self {
+        Self { elapsed: sec.seconds as u64 * 1_000_000 }
+    }
+
+    /// Returns the latest time point, which is `i64::MAX` microseconds as on chain
+    pub fn max() -> Self {
+        Self { elapsed: i64::MAX as u64 }
+    }
+}
+
+impl ops::Add<u64> for TimePoint {
+    type Output = Self;
+
+    /// Adds `rhs` microseconds
+    fn add(self, rhs: u64) -> Self {
+        let elapsed = self.elapsed.checked_add(rhs);
+        check(elapsed.is_some(), "TimePoint: addition overflow");
+        Self { elapsed: elapsed.unwrap_or_default() }
+    }
+}
+
+impl ops::Sub<u64> for TimePoint {
+    type Output = Self;
+
+    /// Subtracts `rhs` microseconds
+    fn sub(self, rhs: u64) -> Self {
+        let elapsed = self.elapsed.checked_sub(rhs);
+        check(elapsed.is_some(), "TimePoint: subtraction underflow");
+        Self { elapsed: elapsed.unwrap_or_default() }
+    }
+}
+
+/// Converts a `Duration` since the Unix epoch to a `TimePoint`,
+/// e.g. `TimePoint::from(Duration::from_secs(3600))` is 1970-01-01T01:00:00
+impl From<Duration> for TimePoint {
+    fn from(value: Duration) -> Self {
+        let elapsed = value.as_micros();
+        check(elapsed <= u64::MAX as u128, "TimePoint: duration overflow");
+        Self { elapsed: elapsed as u64 }
+    }
+}
+
 impl Packer for TimePoint {
     fn size(&self) -> usize {
         return 8;
@@ -591,7 +1180,7 @@ impl Packer for TimePoint {
 
 ///
 #[cfg_attr(feature = "std", derive(eosio_scale_info::TypeInfo))]
-#[derive(Copy, Clone, Eq, PartialEq, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Default)]
 pub struct TimePointSec {
     ///
     pub seconds: u32,
@@ -607,6 +1196,129 @@ impl TimePointSec {
     }
 }
 
+/// Error returned by `TimePointSec::from_iso_string`
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParseTimeError {
+    /// not formatted as `YYYY-MM-DDTHH:MM:SS`
+    BadFormat,
+    /// invalid date or time, or not representable as a `TimePointSec`
+    OutOfRange,
+}
+
+// days since 1970-01-01 of a date of the proleptic Gregorian calendar
+#[cfg(feature = "std")]
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+// inverse of `days_from_civil`
+#[cfg(feature = "std")]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(feature = "std")]
+fn format_iso_seconds(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let secs = seconds % 86400;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day, secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(feature = "std")]
+impl TimePointSec {
+    /// Formats as UTC `YYYY-MM-DDTHH:MM:SS`, the format used by nodeos
+    pub fn to_iso_string(&self) -> String {
+        format_iso_seconds(self.seconds as u64)
+    }
+
+    /// Parses a UTC `YYYY-MM-DDTHH:MM:SS` string, e.g. `2023-05-01T12:30:00`
+    pub fn from_iso_string(s: &str) -> Result<Self, ParseTimeError> {
+        let bytes = s.as_bytes();
+        if bytes.len() != 19 {
+            return Err(ParseTimeError::BadFormat);
+        }
+        for (i, c) in bytes.iter().enumerate() {
+            let ok = match i {
+                4 | 7 => *c == b'-',
+                10 => *c == b'T',
+                13 | 16 => *c == b':',
+                _ => c.is_ascii_digit(),
+            };
+            if !ok {
+                return Err(ParseTimeError::BadFormat);
+            }
+        }
+
+        let field = |start: usize, end: usize| -> u32 {
+            bytes[start..end].iter().fold(0, |acc, c| acc * 10 + (c - b'0') as u32)
+        };
+        let (year, month, day) = (field(0, 4), field(5, 7), field(8, 10));
+        let (hour, minute, second) = (field(11, 13), field(14, 16), field(17, 19));
+
+        let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap => 29,
+            2 => 28,
+            _ => return Err(ParseTimeError::OutOfRange),
+        };
+        if day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 59 {
+            return Err(ParseTimeError::OutOfRange);
+        }
+
+        let days = days_from_civil(year as i64, month, day);
+        let seconds = days * 86400 + (hour * 3600 + minute * 60 + second) as i64;
+        if !(0..=u32::MAX as i64).contains(&seconds) {
+            return Err(ParseTimeError::OutOfRange);
+        }
+        Ok(Self { seconds: seconds as u32 })
+    }
+}
+
+#[cfg(feature = "std")]
+impl TimePoint {
+    /// Formats as UTC `YYYY-MM-DDTHH:MM:SS.sss`, the format used by nodeos
+    pub fn to_iso_string(&self) -> String {
+        format!("{}.{:03}", format_iso_seconds(self.elapsed / 1_000_000), self.elapsed % 1_000_000 / 1000)
+    }
+}
+
+/// Truncates to whole seconds
+impl From<TimePoint> for TimePointSec {
+    fn from(tp: TimePoint) -> Self {
+        let seconds = tp.elapsed / 1_000_000;
+        check(seconds <= u32::MAX as u64, "TimePointSec: time point overflow");
+        Self { seconds: seconds as u32 }
+    }
+}
+
+impl ops::Add<u32> for TimePointSec {
+    type Output = Self;
+
+    /// Adds `rhs` seconds
+    fn add(self, rhs: u32) -> Self {
+        let seconds = self.seconds.checked_add(rhs);
+        check(seconds.is_some(), "TimePointSec: addition overflow");
+        Self { seconds: seconds.unwrap_or_default() }
+    }
+}
+
 impl Packer for TimePointSec {
     fn size(&self) -> usize {
         return 4;
@@ -624,12 +1336,33 @@ impl Packer for TimePointSec {
 
 ///
 #[cfg_attr(feature = "std", derive(eosio_scale_info::TypeInfo))]
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Eq, PartialEq, Default)]
 pub struct BlockTimeStampType {
     ///
     pub slot: u32,
 }
 
+impl BlockTimeStampType {
+    /// block interval in milliseconds
+    pub const BLOCK_INTERVAL_MS: u64 = 500;
+    /// 2000-01-01T00:00:00 in milliseconds since the Unix epoch
+    pub const BLOCK_TIMESTAMP_EPOCH_MS: u64 = 946684800000;
+
+    /// Converts a time point to the slot containing it, time points before the epoch map to slot 0
+    pub fn from_time_point(tp: TimePoint) -> Self {
+        let ms = tp.elapsed / 1000;
+        let slot = ms.saturating_sub(Self::BLOCK_TIMESTAMP_EPOCH_MS) / Self::BLOCK_INTERVAL_MS;
+        check(slot <= u32::MAX as u64, "BlockTimeStampType: time point overflow");
+        Self { slot: slot as u32 }
+    }
+
+    /// Returns the time point at the start of the slot
+    pub fn to_time_point(&self) -> TimePoint {
+        let ms = self.slot as u64 * Self::BLOCK_INTERVAL_MS + Self::BLOCK_TIMESTAMP_EPOCH_MS;
+        TimePoint { elapsed: ms * 1000 }
+    }
+}
+
 impl Packer for BlockTimeStampType {
     fn size(&self) -> usize {
         return 4;
@@ -684,6 +1417,13 @@ impl Packer for ProducerKey {
         dec.unpack(&mut self.block_signing_key);
         return dec.get_pos();
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        let mut dec = Decoder::new(raw);
+        dec.try_unpack(&mut self.producer_name)?;
+        dec.try_unpack(&mut self.block_signing_key)?;
+        Ok(dec.get_pos())
+    }
 }
 
 impl Default for ProducerKey {
@@ -694,7 +1434,7 @@ impl Default for ProducerKey {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Eq, PartialEq, Default)]
 pub struct KeyWeight {
     pub key: PublicKey,
     pub weight: u16,
@@ -720,9 +1460,17 @@ impl Packer for KeyWeight {
         dec.unpack(&mut self.weight);
         return dec.get_pos();
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        let size = self.key.try_unpack(raw)?;
+        let mut weight = [0u8; 2];
+        try_unpack_bytes(&mut weight, &raw[size..])?;
+        self.weight = u16::from_le_bytes(weight);
+        Ok(size + 2)
+    }
 }
 
-#[derive(Default)]
+#[derive(Clone, Eq, PartialEq, Default)]
 pub struct BlockSigningAuthorityV0 {
     /**
      * minimum threshold of accumulated weights from component keys that satisfies this authority
@@ -741,7 +1489,7 @@ pub struct BlockSigningAuthorityV0 {
 
 impl Packer for BlockSigningAuthorityV0 {
     fn size(&self) -> usize {
-        let mut size = 2 + VarUint32::new(self.keys.len() as u32).size();
+        let mut size = 4 + VarUint32::new(self.keys.len() as u32).size();
         for key in &self.keys {
             size += key.size();
         }
@@ -763,8 +1511,25 @@ impl Packer for BlockSigningAuthorityV0 {
         dec.unpack(&mut self.keys);
         return dec.get_pos();
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        let mut threshold = [0u8; 4];
+        let mut pos = try_unpack_bytes(&mut threshold, raw)?;
+        self.threshold = u32::from_le_bytes(threshold);
+
+        let mut count = VarUint32::default();
+        pos += count.try_unpack(&raw[pos..])?;
+        self.keys.clear();
+        for _ in 0..count.value() {
+            let mut key = KeyWeight::default();
+            pos += key.try_unpack(&raw[pos..])?;
+            self.keys.push(key);
+        }
+        Ok(pos)
+    }
 }
 
+#[derive(Clone, Eq, PartialEq)]
 pub enum BlockSigningAuthority {
     V0(BlockSigningAuthorityV0)
 }
@@ -806,10 +1571,21 @@ impl Packer for BlockSigningAuthority {
         }
         return dec.get_pos();
     }
+
+    fn try_unpack(&mut self, raw: &[u8]) -> Result<usize, DecodeError> {
+        let ty = *raw.first().ok_or(DecodeError::BufferOverflow)?;
+        if ty != 0 {
+            return Err(DecodeError::BadVariant { name: "BlockSigningAuthority", tag: ty as u32 });
+        }
+        let mut v0 = BlockSigningAuthorityV0::default();
+        let size = v0.try_unpack(&raw[1..])?;
+        *self = BlockSigningAuthority::V0(v0);
+        Ok(1 + size)
+    }
 }
 
 
-#[derive(Default)]
+#[derive(Clone, Eq, PartialEq, Default)]
 pub struct ProducerAuthority {
 
     /**
@@ -846,3 +1622,474 @@ impl Packer for ProducerAuthority {
         return dec.get_pos();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_key(seed: u8) -> PublicKey {
+        let mut key = ECCPublicKey::default();
+        key.data[0] = 2;
+        key.data[1..].fill(seed);
+        PublicKey::K1(key)
+    }
+
+    #[test]
+    fn test_producer_schedule_pack_unpack() {
+        let schedule: Vec<ProducerAuthority> = vec![
+            ProducerAuthority {
+                producer_name: Name::new("alice"),
+                authority: BlockSigningAuthority::V0(BlockSigningAuthorityV0 {
+                    threshold: 1,
+                    keys: vec![KeyWeight{ key: new_key(1), weight: 1 }],
+                }),
+            },
+            ProducerAuthority {
+                producer_name: Name::new("bob"),
+                authority: BlockSigningAuthority::V0(BlockSigningAuthorityV0 {
+                    threshold: 2,
+                    keys: vec![
+                        KeyWeight{ key: new_key(2), weight: 1 },
+                        KeyWeight{ key: new_key(3), weight: 1 },
+                    ],
+                }),
+            },
+        ];
+
+        let packed = Encoder::pack(&schedule);
+        let mut unpacked: Vec<ProducerAuthority> = Vec::new();
+        let size = unpacked.unpack(&packed);
+
+        assert_eq!(size, packed.len());
+        assert!(schedule == unpacked);
+        assert_eq!(Encoder::pack(&unpacked), packed);
+    }
+
+    #[test]
+    fn test_authority_size() {
+        let key_weight = KeyWeight{ key: new_key(1), weight: 1 };
+        assert_eq!(key_weight.size(), Encoder::pack(&key_weight).len());
+
+        let mut v0 = BlockSigningAuthorityV0 { threshold: 3, keys: Vec::new() };
+        assert_eq!(v0.size(), Encoder::pack(&v0).len());
+
+        // more than 127 bytes of keys, but less than 128 keys
+        for i in 0..4 {
+            v0.keys.push(KeyWeight{ key: new_key(i), weight: 1 });
+        }
+        assert_eq!(v0.size(), Encoder::pack(&v0).len());
+        assert_eq!(v0.keys.size(), Encoder::pack(&v0.keys).len());
+
+        let producer = ProducerAuthority {
+            producer_name: Name::new("alice"),
+            authority: BlockSigningAuthority::V0(v0),
+        };
+        assert_eq!(producer.size(), Encoder::pack(&producer).len());
+    }
+
+    #[test]
+    fn test_uint256_pack_little_endian() {
+        let value = Uint256::new(0x0102, 0x0304);
+        let packed = Encoder::pack(&value);
+        let mut expected = [0u8; 32];
+        expected[0] = 0x02;
+        expected[1] = 0x01;
+        expected[16] = 0x04;
+        expected[17] = 0x03;
+        assert_eq!(packed, expected);
+
+        let mut unpacked = Uint256::default();
+        unpacked.unpack(&packed);
+        assert!(unpacked == value);
+    }
+
+    #[test]
+    fn test_time_point_duration() {
+        let time_point = TimePoint::from(Duration::from_secs(3600));
+        assert_eq!(time_point.elapsed, 3600_000_000);
+        assert_eq!(time_point.to_duration(), Duration::from_secs(3600));
+
+        let duration = Duration::from_micros(1_500_001);
+        assert_eq!(TimePoint::from(duration).to_duration(), duration);
+
+        // sub-microsecond precision is truncated
+        assert_eq!(TimePoint::from(Duration::from_nanos(1_999)).elapsed, 1);
+    }
+
+    #[test]
+    fn test_rpid() {
+        let mut key = WebAuthNPublicKey::default();
+        for rpid in ["example.com", "localhost", "a-b.example.com", "EXAMPLE.com"] {
+            key.rpid = rpid.into();
+            assert!(key.is_valid_rpid(), "{}", rpid);
+        }
+
+        for rpid in ["", ".com", "example..com", "-a.com", "a-.com", "exämple.com", "a b.com"] {
+            key.rpid = rpid.into();
+            assert!(!key.is_valid_rpid(), "{}", rpid);
+        }
+    }
+
+    #[test]
+    fn test_rpid_size() {
+        let mut key = WebAuthNPublicKey::default();
+        key.rpid = "a".repeat(MAX_RPID_SIZE);
+        let packed = Encoder::pack(&key);
+        assert_eq!(packed.len(), key.size());
+        let mut unpacked = WebAuthNPublicKey::default();
+        assert_eq!(unpacked.try_unpack(&packed), Ok(packed.len()));
+        assert_eq!(unpacked.rpid, key.rpid);
+
+        // `pack` rejects the over-long rpid with `check`, so build the packed key by hand
+        let mut enc = Encoder::new(0);
+        key.key.pack(&mut enc);
+        key.user_presence.pack(&mut enc);
+        "a".repeat(MAX_RPID_SIZE + 1).pack(&mut enc);
+        assert_eq!(WebAuthNPublicKey::default().try_unpack(enc.get_bytes()), Err(DecodeError::Malformed));
+        assert!(!WebAuthNPublicKey::new(key.key, key.user_presence, "a".repeat(MAX_RPID_SIZE + 1)).is_valid_rpid());
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn test_ecc_public_key_uncompressed() {
+        // the generator point of secp256k1
+        let compressed = ECCPublicKey::from_hex("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+        let uncompressed = decode_hex("0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8");
+
+        assert_eq!(compressed.to_uncompressed().to_vec(), uncompressed);
+
+        let mut data = [0u8; 65];
+        data.copy_from_slice(&uncompressed);
+        assert!(ECCPublicKey::from_uncompressed(&data) == Some(compressed));
+
+        // not on the curve
+        data[64] ^= 1;
+        assert!(ECCPublicKey::from_uncompressed(&data).is_none());
+    }
+
+    #[cfg(feature = "crypto")]
+    #[test]
+    fn test_signature_canonical() {
+        let mut raw = [0u8; 65];
+        raw[0] = 31;
+        raw[1..33].fill(0x11);
+        raw[64] = 1;
+
+        let mut sig = Signature::default();
+        sig.data = raw;
+        assert!(sig.is_canonical());
+
+        let high_s = sig.to_high_s();
+        assert!(!high_s.is_canonical());
+        assert_eq!(high_s.data[0], 32);
+        assert_eq!(high_s.data[1..33], raw[1..33]);
+        assert_eq!(high_s.data[33..65], SECP256K1_ORDER[..31].iter().chain(&[0x40]).cloned().collect::<Vec<u8>>()[..]);
+        assert!(high_s.to_high_s() == sig);
+
+        sig.data[33..].copy_from_slice(&SECP256K1_HALF_ORDER);
+        assert!(sig.is_canonical());
+        sig.data[64] += 1;
+        assert!(!sig.is_canonical());
+
+        // a header byte below 27 must not underflow
+        let zero = Signature::default().to_high_s();
+        assert_eq!(zero.data[0], 31);
+        assert_eq!(zero.data[33..], SECP256K1_ORDER[..]);
+        assert!(!zero.is_canonical());
+
+        // an uncompressed header gets the compressed one
+        sig.data = raw;
+        sig.data[0] = 27;
+        assert_eq!(sig.to_high_s().data[0], 32);
+    }
+
+    #[test]
+    fn test_block_timestamp() {
+        let epoch = TimePoint { elapsed: 946684800_000_000 };
+        assert_eq!(BlockTimeStampType::from_time_point(epoch).slot, 0);
+
+        // 2020-01-01T00:00:00
+        let tp = TimePoint::from(Duration::from_secs(1577836800));
+        let timestamp = BlockTimeStampType::from_time_point(tp);
+        assert_eq!(timestamp.slot, 1262304000);
+        assert!(timestamp.to_time_point() == tp);
+
+        // 2020-01-01T00:00:00.750 is in the second half-second slot
+        let tp = TimePoint::from(Duration::from_millis(1577836800_750));
+        let timestamp = BlockTimeStampType::from_time_point(tp);
+        assert_eq!(timestamp.slot, 1262304001);
+        assert_eq!(timestamp.to_time_point().elapsed, 1577836800_500_000);
+    }
+
+    #[test]
+    fn test_mixed_signatures() {
+        let mut k1 = [0u8; 65];
+        k1[0] = 31;
+        let mut r1 = [0u8; 65];
+        r1[0] = 32;
+        r1[64] = 0xff;
+        let signatures = vec![
+            Signature::new(SIGNATURE_TYPE_K1, k1),
+            Signature::new(SIGNATURE_TYPE_R1, r1),
+        ];
+
+        let packed = Encoder::pack(&signatures);
+        assert_eq!(packed.len(), 1 + 66 * 2);
+        assert_eq!(packed[1], SIGNATURE_TYPE_K1);
+        assert_eq!(packed[1 + 66], SIGNATURE_TYPE_R1);
+
+        let mut unpacked: Vec<Signature> = Vec::new();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert!(unpacked == signatures);
+        assert_eq!(unpacked[1].ty(), SIGNATURE_TYPE_R1);
+        assert_eq!(unpacked[0].signature_type(), SignatureType::K1);
+        assert_eq!(unpacked[1].signature_type(), SignatureType::R1);
+
+        let hex = ["20", "00".repeat(63).as_str(), "ff"].concat();
+        let r1_from_hex = Signature::from_hex_with_type(SIGNATURE_TYPE_R1, &hex);
+        assert!(r1_from_hex == signatures[1]);
+        assert_eq!(Signature::from_hex(&hex).signature_type(), SignatureType::K1);
+
+        let mut webauthn = packed[1..67].to_vec();
+        webauthn[0] = SIGNATURE_TYPE_WEBAUTHN;
+        assert_eq!(Signature::default().try_unpack(&webauthn), Err(DecodeError::BadVariant { name: "Signature", tag: 2 }));
+    }
+
+    #[test]
+    fn test_try_unpack() {
+        use crate::serializer::try_unpack_exact;
+
+        let mut checksum = Checksum256::default();
+        assert_eq!(checksum.try_unpack(&[1u8; 31]), Err(DecodeError::BufferOverflow));
+        assert_eq!(checksum.try_unpack(&[1u8; 33]), Ok(32));
+        assert!(checksum.data == [1u8; 32]);
+        assert_eq!(try_unpack_exact::<Checksum256>(&[1u8; 33]).err(), Some(DecodeError::TrailingBytes));
+        assert_eq!(Checksum160::default().try_unpack(&[0u8; 19]), Err(DecodeError::BufferOverflow));
+        assert_eq!(Checksum512::default().try_unpack(&[0u8; 64]), Ok(64));
+
+        let key = new_key(7);
+        let packed = Encoder::pack(&key);
+        assert!(try_unpack_exact::<PublicKey>(&packed).unwrap() == key);
+        assert_eq!(PublicKey::default().try_unpack(&packed[..20]), Err(DecodeError::BufferOverflow));
+        assert_eq!(PublicKey::default().try_unpack(&[]), Err(DecodeError::BufferOverflow));
+        let mut bad_type = packed.clone();
+        bad_type[0] = 3;
+        assert_eq!(PublicKey::default().try_unpack(&bad_type), Err(DecodeError::BadVariant { name: "PublicKey", tag: 3 }));
+
+        let webauthn = PublicKey::WebAuth(WebAuthNPublicKey::new(ECCPublicKey::default(), UserPresence::Present, "example.com".into()));
+        let packed = Encoder::pack(&webauthn);
+        assert!(try_unpack_exact::<PublicKey>(&packed).unwrap() == webauthn);
+        assert_eq!(PublicKey::default().try_unpack(&packed[..packed.len() - 1]), Err(DecodeError::BufferOverflow));
+        let mut bad_presence = packed.clone();
+        bad_presence[34] = 3;
+        assert_eq!(PublicKey::default().try_unpack(&bad_presence), Err(DecodeError::BadVariant { name: "UserPresence", tag: 3 }));
+
+        let mut signature = [0u8; 66];
+        signature[0] = 2;
+        assert_eq!(Signature::default().try_unpack(&signature), Err(DecodeError::BadVariant { name: "Signature", tag: 2 }));
+        signature[0] = SIGNATURE_TYPE_R1;
+        assert_eq!(Signature::default().try_unpack(&signature), Ok(66));
+        assert_eq!(Signature::default().try_unpack(&signature[..65]), Err(DecodeError::BufferOverflow));
+    }
+
+    #[test]
+    fn test_checksum_hex() {
+        use crate::string::ToString;
+
+        let hex = "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
+        let checksum: Checksum256 = hex.parse().unwrap();
+        assert_eq!(checksum.data[1], 0x11);
+        assert_eq!(checksum.to_string(), hex);
+        assert!(Checksum256::from_hex(hex) == checksum);
+        assert!(Checksum256::from_hex(&hex.to_uppercase()) == checksum);
+
+        assert_eq!(hex[2..].parse::<Checksum256>().err(), Some(ParseHexError::BadLength));
+        assert_eq!(hex.replace('a', "g").parse::<Checksum256>().err(), Some(ParseHexError::BadHex));
+        assert_eq!(("+".to_string() + &hex[1..]).parse::<Checksum256>().err(), Some(ParseHexError::BadHex));
+
+        let checksum = Checksum160{data: [0xab; 20]};
+        assert_eq!(checksum.to_string(), "ab".repeat(20));
+        assert!(checksum.to_string().parse::<Checksum160>().unwrap() == checksum);
+
+        let checksum = Checksum512{data: [0x0f; 64]};
+        assert_eq!(checksum.to_string(), "0f".repeat(64));
+        assert!(checksum.to_string().parse::<Checksum512>().unwrap() == checksum);
+    }
+
+    #[test]
+    fn test_uint128_arithmetic() {
+        let max_lo = Uint128{lo: u64::MAX, hi: 0};
+        let one = Uint128::from(1u128);
+
+        let sum = max_lo + one;
+        assert_eq!((sum.lo, sum.hi), (0, 1));
+        assert_eq!(u128::from(sum), 1u128 << 64);
+        assert!(sum - one == max_lo);
+        assert!(max_lo * Uint128::from(2u128) == Uint128{lo: u64::MAX - 1, hi: 1});
+        assert!(sum / Uint128::from(2u128) == Uint128{lo: 1 << 63, hi: 0});
+
+        let max = Uint128::from(u128::MAX);
+        assert_eq!((max.lo, max.hi), (u64::MAX, u64::MAX));
+        assert!(max.checked_add(one).is_none());
+        assert!(Uint128::default().checked_sub(one).is_none());
+        assert!(max.checked_mul(Uint128::from(2u128)).is_none());
+        assert!(max.checked_div(Uint128::default()).is_none());
+        assert!(max_lo.checked_add(one) == Some(sum));
+
+        let (value, overflow) = max.overflowing_add(one);
+        assert!(value == Uint128::default() && overflow);
+        let (value, overflow) = Uint128::default().overflowing_sub(one);
+        assert!(value == max && overflow);
+        let (value, overflow) = max_lo.overflowing_mul(max_lo);
+        assert!(!overflow);
+        assert_eq!(u128::from(value), (u64::MAX as u128) * (u64::MAX as u128));
+    }
+
+    #[test]
+    fn test_int128_arithmetic() {
+        let min = Int128::from(i128::MIN);
+        assert_eq!((min.lo, min.hi), (0, 1 << 63));
+        assert_eq!(i128::from(min), i128::MIN);
+        let minus_one = Int128::from(-1i128);
+        assert_eq!((minus_one.lo, minus_one.hi), (u64::MAX, u64::MAX));
+
+        let one = Int128::from(1i128);
+        let near_min = min + one;
+        assert_eq!(i128::from(near_min), i128::MIN + 1);
+        assert!(near_min - one == min);
+        assert_eq!(i128::from(-near_min), i128::MAX);
+        assert!(min.checked_sub(one).is_none());
+        assert!(min.checked_neg().is_none());
+        assert!(Int128::from(i128::MAX).checked_add(one).is_none());
+
+        // mixed-sign subtraction crossing zero
+        let three = Int128::from(3i128);
+        let five = Int128::from(5i128);
+        assert_eq!(i128::from(three - five), -2);
+        assert_eq!(i128::from(Int128::from(-2i128) - Int128::from(-5i128)), 3);
+        assert!(Int128::from(u64::MAX as i128) - Int128::from(u64::MAX as i128 + 1) == minus_one);
+
+        assert!(min < near_min);
+        assert!(minus_one < Int128::default());
+        assert!(Int128::default() < one);
+        assert!(Int128::from(-(1i128 << 64)) < minus_one);
+        assert!(Int128::from(i128::MAX) > Int128::from(u64::MAX as i128));
+        let mut values = [one, min, minus_one, Int128::from(i128::MAX), Int128::default()];
+        values.sort();
+        let sorted: [i128; 5] = [i128::MIN, -1, 0, 1, i128::MAX];
+        for (value, expected) in values.iter().zip(sorted.iter()) {
+            assert_eq!(i128::from(*value), *expected);
+        }
+    }
+
+    #[test]
+    fn test_public_key_bytes() {
+        let mut data = [0x11u8; 33];
+        data[0] = 0x02;
+        let key = ECCPublicKey::from_bytes(&data);
+        assert_eq!(key.as_bytes(), &data);
+        assert!(key == ECCPublicKey::from_hex(&"11".repeat(33).replacen("11", "02", 1)));
+
+        let k1 = PublicKey::from_k1_bytes(&data);
+        let packed = Encoder::pack(&k1);
+        assert_eq!(packed[0], 0);
+        assert_eq!(&packed[1..], &data);
+        assert!(k1 == PublicKey::K1(key));
+
+        let r1 = PublicKey::from_r1_bytes(&data);
+        assert_eq!(Encoder::pack(&r1)[0], 1);
+        assert!(r1 == PublicKey::R1(key));
+    }
+
+    #[test]
+    fn test_time_point_conversions() {
+        // block time of 2023-05-01T12:30:00.500
+        let block_time = TimePoint { elapsed: 1_682_944_200_500_000 };
+        let expiration = TimePointSec::from(block_time) + 30;
+        assert_eq!(expiration.seconds(), 1_682_944_230);
+        assert!(TimePoint::from_sec(expiration) > block_time);
+        assert!(TimePoint::from_sec(expiration) == block_time + 29_500_000);
+        assert!(block_time - 500_000 == TimePoint::from_sec(TimePointSec::new(1_682_944_200)));
+
+        assert!(TimePointSec::new(1) < expiration);
+        assert!(TimePoint::max() > block_time);
+        assert_eq!(TimePoint::max().elapsed, i64::MAX as u64);
+        assert_eq!(TimePoint::from_sec(TimePointSec::new(u32::MAX)).elapsed, u32::MAX as u64 * 1_000_000);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_iso_string() {
+        let tp = TimePointSec::from_iso_string("2023-05-01T12:30:00").unwrap();
+        assert_eq!(tp.seconds(), 1_682_944_200);
+        assert_eq!(tp.to_iso_string(), "2023-05-01T12:30:00");
+        assert_eq!(TimePointSec::default().to_iso_string(), "1970-01-01T00:00:00");
+        assert_eq!(TimePointSec::new(u32::MAX).to_iso_string(), "2106-02-07T06:28:15");
+        assert_eq!(TimePoint { elapsed: 1_682_944_200_500_000 }.to_iso_string(), "2023-05-01T12:30:00.500");
+
+        // leap years
+        for s in ["2024-02-29T00:00:00", "2000-02-29T23:59:59", "2023-12-31T23:59:59", "2024-03-01T00:00:00"] {
+            assert_eq!(TimePointSec::from_iso_string(s).unwrap().to_iso_string(), s);
+        }
+        let feb29 = TimePointSec::from_iso_string("2024-02-29T00:00:00").unwrap();
+        assert!(feb29 + 86400 == TimePointSec::from_iso_string("2024-03-01T00:00:00").unwrap());
+        assert_eq!(TimePointSec::from_iso_string("2023-02-29T00:00:00").err(), Some(ParseTimeError::OutOfRange));
+        assert_eq!(TimePointSec::from_iso_string("2100-02-29T00:00:00").err(), Some(ParseTimeError::OutOfRange));
+
+        assert_eq!(TimePointSec::from_iso_string("2023-13-01T00:00:00").err(), Some(ParseTimeError::OutOfRange));
+        assert_eq!(TimePointSec::from_iso_string("2023-05-01T24:00:00").err(), Some(ParseTimeError::OutOfRange));
+        assert_eq!(TimePointSec::from_iso_string("1969-12-31T23:59:59").err(), Some(ParseTimeError::OutOfRange));
+        assert_eq!(TimePointSec::from_iso_string("2106-02-07T06:28:16").err(), Some(ParseTimeError::OutOfRange));
+        assert_eq!(TimePointSec::from_iso_string("2023-05-01 12:30:00").err(), Some(ParseTimeError::BadFormat));
+        assert_eq!(TimePointSec::from_iso_string("2023-05-01T12:30").err(), Some(ParseTimeError::BadFormat));
+        assert_eq!(TimePointSec::from_iso_string("2023-05-01T12:3a:00").err(), Some(ParseTimeError::BadFormat));
+        assert_eq!(TimePointSec::from_iso_string("2023-05-01T12:30:00é").err(), Some(ParseTimeError::BadFormat));
+    }
+
+    #[test]
+    fn test_block_signing_authority_try_unpack() {
+        use crate::serializer::try_unpack_exact;
+
+        let mut key = [0x11u8; 33];
+        key[0] = 0x02;
+        let authority = BlockSigningAuthority::V0(BlockSigningAuthorityV0 {
+            threshold: 1,
+            keys: vec![KeyWeight { key: PublicKey::from_k1_bytes(&key), weight: 1 }],
+        });
+        let packed = Encoder::pack(&authority);
+        assert_eq!(packed.len(), authority.size());
+        assert!(try_unpack_exact::<BlockSigningAuthority>(&packed).unwrap() == authority);
+        assert_eq!(BlockSigningAuthority::default().try_unpack(&packed[..packed.len() - 1]), Err(DecodeError::BufferOverflow));
+
+        let mut bad_tag = packed.clone();
+        bad_tag[0] = 1;
+        assert_eq!(BlockSigningAuthority::default().try_unpack(&bad_tag), Err(DecodeError::BadVariant { name: "BlockSigningAuthority", tag: 1 }));
+
+        // unknown tag of a key inside of the authority
+        let mut bad_key = packed.clone();
+        bad_key[6] = 3;
+        assert_eq!(BlockSigningAuthority::default().try_unpack(&bad_key), Err(DecodeError::BadVariant { name: "PublicKey", tag: 3 }));
+    }
+
+    #[test]
+    fn test_decoder_finish() {
+        let mut key = [0x22u8; 33];
+        key[0] = 0x03;
+        let producer_key = ProducerKey::new(Name::new("alice"), PublicKey::from_k1_bytes(&key));
+        let mut packed = Encoder::pack(&producer_key);
+
+        let mut dec = Decoder::new(&packed);
+        let mut unpacked = ProducerKey::default();
+        assert_eq!(dec.try_unpack(&mut unpacked), Ok(packed.len()));
+        assert!(unpacked == producer_key);
+        assert_eq!(dec.remaining(), 0);
+        assert_eq!(dec.finish(), Ok(()));
+
+        packed.extend_from_slice(&[0xde, 0xad]);
+        let mut dec = Decoder::new(&packed);
+        let mut unpacked = ProducerKey::default();
+        assert_eq!(dec.try_unpack(&mut unpacked), Ok(packed.len() - 2));
+        assert_eq!(dec.remaining(), 2);
+        assert_eq!(dec.finish(), Err(DecodeError::TrailingBytes));
+    }
+}
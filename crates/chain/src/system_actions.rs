@@ -0,0 +1,215 @@
+//! Typed arguments of the most common system actions,
+//! which allows packing them locally instead of going through an ABI.
+
+use crate::serializer::{
+    Packer,
+    Encoder,
+    Decoder,
+};
+
+use crate::name::{
+    Name,
+};
+
+use crate::action::{
+    PermissionLevel,
+};
+
+use crate::structs::{
+    KeyWeight,
+};
+
+use crate::asset::{
+    Asset,
+};
+
+use crate::{
+    vec::Vec,
+    string::String,
+};
+
+use crate::vmapi::eosio::{
+    check,
+};
+
+use structpacker::StructPacker;
+
+///
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct PermissionLevelWeight {
+    ///
+    pub permission: PermissionLevel,
+    ///
+    pub weight: u16,
+}
+
+///
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct WaitWeight {
+    ///
+    pub wait_sec: u32,
+    ///
+    pub weight: u16,
+}
+
+///
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct Authority {
+    ///
+    pub threshold: u32,
+    ///
+    pub keys: Vec<KeyWeight>,
+    ///
+    pub accounts: Vec<PermissionLevelWeight>,
+    ///
+    pub waits: Vec<WaitWeight>,
+}
+
+/// Arguments of `eosio::newaccount`
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct NewAccount {
+    ///
+    pub creator: Name,
+    ///
+    pub name: Name,
+    ///
+    pub owner: Authority,
+    ///
+    pub active: Authority,
+}
+
+/// Arguments of `eosio::setcode`
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct SetCode {
+    ///
+    pub account: Name,
+    ///
+    pub vmtype: u8,
+    ///
+    pub vmversion: u8,
+    ///
+    pub code: Vec<u8>,
+}
+
+/// Arguments of `eosio::setabi`
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct SetAbi {
+    ///
+    pub account: Name,
+    /// packed abi
+    pub abi: Vec<u8>,
+}
+
+/// Arguments of `eosio::buyram`
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct BuyRam {
+    ///
+    pub payer: Name,
+    ///
+    pub receiver: Name,
+    ///
+    pub quant: Asset,
+}
+
+/// Arguments of `eosio::delegatebw`
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct DelegateBw {
+    ///
+    pub from: Name,
+    ///
+    pub receiver: Name,
+    ///
+    pub stake_net_quantity: Asset,
+    ///
+    pub stake_cpu_quantity: Asset,
+    ///
+    pub transfer: bool,
+}
+
+/// Maximum size of a memo accepted by `eosio.token::transfer`
+pub const MAX_MEMO_SIZE: usize = 256;
+
+/// Checks that `memo` does not exceed `MAX_MEMO_SIZE` bytes
+pub fn is_valid_memo(memo: &str) -> bool {
+    memo.len() <= MAX_MEMO_SIZE
+}
+
+/// Arguments of `eosio.token::transfer`
+#[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+pub struct Transfer {
+    ///
+    pub from: Name,
+    ///
+    pub to: Name,
+    ///
+    pub quantity: Asset,
+    ///
+    pub memo: String,
+}
+
+impl Transfer {
+    ///
+    pub fn new(from: Name, to: Name, quantity: Asset, memo: String) -> Self {
+        check(is_valid_memo(&memo), "memo has more than 256 bytes");
+        Self { from, to, quantity, memo }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_pack() {
+        let transfer = Transfer {
+            from: Name::new("alice"),
+            to: Name::new("bob"),
+            quantity: Asset::from_string("1.0000 EOS"),
+            memo: "hello".into(),
+        };
+
+        let packed = Encoder::pack(&transfer);
+        assert_eq!(packed.len(), transfer.size());
+        assert_eq!(&packed[0..8], &Name::new("alice").value().to_le_bytes());
+        assert_eq!(&packed[8..16], &Name::new("bob").value().to_le_bytes());
+        assert_eq!(&packed[16..24], &10000i64.to_le_bytes());
+        assert_eq!(&packed[32..], &[5, b'h', b'e', b'l', b'l', b'o']);
+
+        let mut unpacked = Transfer::default();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert!(unpacked == transfer);
+    }
+
+    #[test]
+    fn test_newaccount_pack() {
+        let mut owner = Authority::default();
+        owner.threshold = 1;
+        owner.accounts.push(PermissionLevelWeight {
+            permission: PermissionLevel::new(Name::new("alice"), Name::new("eosio.code")),
+            weight: 1,
+        });
+
+        let newaccount = NewAccount {
+            creator: Name::new("eosio"),
+            name: Name::new("alice"),
+            owner: owner.clone(),
+            active: owner,
+        };
+
+        let packed = Encoder::pack(&newaccount);
+        assert_eq!(packed.len(), newaccount.size());
+
+        let mut unpacked = NewAccount::default();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert!(unpacked == newaccount);
+    }
+
+    #[test]
+    fn test_memo_size() {
+        assert!(is_valid_memo(""));
+        assert!(is_valid_memo(&"a".repeat(MAX_MEMO_SIZE)));
+        assert!(!is_valid_memo(&"a".repeat(MAX_MEMO_SIZE + 1)));
+        // the limit is in bytes, not characters
+        assert!(!is_valid_memo(&"é".repeat(MAX_MEMO_SIZE / 2 + 1)));
+    }
+}
@@ -0,0 +1,131 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use chaintester::ChainTester;
+use chaintester::client::{
+    ChainTesterError,
+    GetTableRowsPrams,
+    Result,
+    TableRows,
+    decode_json_row,
+    decode_table_rows,
+};
+
+use crate::action_trace::parse_hex;
+use crate::asset::{
+    Asset,
+    SymbolCode,
+};
+use crate::name::Name;
+use crate::serializer::{
+    Packer,
+    Encoder,
+    Decoder,
+    try_unpack_exact,
+};
+use crate::StructPacker;
+
+/// Reads rows of a table as `T`, from their json form if `params.json` is set and from their packed form otherwise.
+/// Optional fields (`T?` in the ABI) are `Option<T>` fields, which pack the same way
+/// and are `None` when the field is null or missing in the json form.
+pub fn get_table_rows_typed<T: Packer + Default + DeserializeOwned>(tester: &mut ChainTester, params: &GetTableRowsPrams) -> Result<TableRows<T>> {
+    let ret = tester.get_table_rows_ex(params)?;
+    if params.json {
+        decode_table_rows(&ret, decode_json_row)
+    } else {
+        decode_table_rows(&ret, decode_packed_row)
+    }
+}
+
+fn decode_packed_row<T: Packer + Default>(data: &Value) -> core::result::Result<T, String> {
+    let data = data.as_str().and_then(parse_hex).ok_or_else(|| String::from("invalid packed row"))?;
+    try_unpack_exact::<T>(&data).map_err(|err| format!("{:?}", err))
+}
+
+/// Row of the `stat` table of a token contract
+#[derive(Default, StructPacker)]
+struct CurrencyStats {
+    supply: Asset,
+    max_supply: Asset,
+    issuer: Name,
+}
+
+/// Returns the balance of `account` in the token `code` of `token_account`, e.g. `1.0000 EOS`,
+/// or a zero balance with the precision of the token if `account` holds none of it.
+pub fn get_balance(tester: &mut ChainTester, account: &str, token_account: &str, code: SymbolCode) -> Result<Asset> {
+    let code_str = code.to_string();
+    let params = GetTableRowsPrams::builder()
+        .json(false)
+        .code(token_account)
+        .scope(account)
+        .table("accounts")
+        .lower_bound(&code_str)
+        .limit(1)
+        .build();
+    let ret = tester.get_table_rows_ex(&params)?;
+    // the row of the next symbol is returned when `account` has no balance of `code`
+    let balance = decode_table_rows(&ret, decode_packed_row::<Asset>)?.rows.into_iter().find(|balance| balance.symbol().code() == code);
+    if let Some(balance) = balance {
+        return Ok(balance);
+    }
+
+    let params = GetTableRowsPrams::builder()
+        .json(false)
+        .code(token_account)
+        .scope(&code_str)
+        .table("stat")
+        .lower_bound(&code_str)
+        .limit(1)
+        .build();
+    let ret = tester.get_table_rows_ex(&params)?;
+    match decode_table_rows(&ret, decode_packed_row::<CurrencyStats>)?.rows.first() {
+        Some(stats) if stats.supply.symbol().code() == code => Ok(Asset::new(0, stats.supply.symbol())),
+        _ => Err(ChainTesterError{json: None, error_string: Some(format!("token {} of {} does not exist", code_str, token_account))}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::string::String;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Default, StructPacker, serde::Deserialize)]
+    struct Profile {
+        id: u64,
+        nickname: Option<String>,
+        age: Option<u32>,
+    }
+
+    fn to_hex(data: &[u8]) -> String {
+        data.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_optional_fields() {
+        let profiles = vec![
+            Profile{id: 1, nickname: Some("alice".into()), age: None},
+            Profile{id: 2, nickname: None, age: Some(30)},
+        ];
+
+        let rows: Vec<Value> = profiles.iter().map(|p| Value::from(to_hex(&Encoder::pack(p)))).collect();
+        let ret = serde_json::json!({"rows": rows});
+        let decoded = decode_table_rows(&ret, decode_packed_row::<Profile>).unwrap();
+        assert_eq!(decoded.rows, profiles);
+
+        // with show_payer
+        let ret = serde_json::json!({"rows": [{"data": to_hex(&Encoder::pack(&profiles[1])), "payer": "alice"}]});
+        let decoded = decode_table_rows(&ret, decode_packed_row::<Profile>).unwrap();
+        assert_eq!(decoded.rows, profiles[1..]);
+
+        // absent optionals are null in the json form, a missing field is also accepted
+        let ret = serde_json::json!({"rows": [
+            {"id": 1, "nickname": "alice", "age": null},
+            {"id": 2, "age": 30},
+        ]});
+        let decoded = decode_table_rows(&ret, decode_json_row::<Profile>).unwrap();
+        assert_eq!(decoded.rows, profiles);
+
+        let ret = serde_json::json!({"rows": [to_hex(&Encoder::pack(&profiles[0])) + "00"]});
+        assert!(decode_table_rows(&ret, decode_packed_row::<Profile>).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use core::any::Any;
+
+use crate::serializer::{
+    Packer,
+    Encoder,
+    Decoder,
+    DecodeError,
+};
+
+use crate::varint::VarUint32;
+
+use crate::{
+    vec::Vec,
+    boxed::Box,
+};
+
+type DecodeFn = fn(&[u8]) -> Result<(Box<dyn Any>, usize), DecodeError>;
+
+fn decode<T: Packer + Default + 'static>(data: &[u8]) -> Result<(Box<dyn Any>, usize), DecodeError> {
+    let mut value = T::default();
+    let size = value.try_unpack(data)?;
+    Ok((Box::new(value), size))
+}
+
+/// Packs `value` after a varuint32 `tag` identifying its type, the self-describing format
+/// read by `TaggedDecoder`. This is not part of the standard encoding, it is meant for interop
+/// with off-chain formats mixing several types in the same stream.
+pub fn pack_tagged<T: Packer>(tag: u32, value: &T) -> Vec<u8> {
+    let tag = VarUint32::new(tag);
+    let mut enc = Encoder::new(tag.size() + value.size());
+    tag.pack(&mut enc);
+    value.pack(&mut enc);
+    enc.get_bytes().to_vec()
+}
+
+/// Decodes data prefixed with a varuint32 type tag into the type registered for that tag
+#[derive(Default)]
+pub struct TaggedDecoder {
+    types: Vec<(u32, DecodeFn)>,
+}
+
+impl TaggedDecoder {
+    ///
+    pub fn new() -> Self {
+        Self { types: Vec::new() }
+    }
+
+    /// Registers `T` for `tag`, replacing the type registered before for the same tag
+    pub fn register<T: Packer + Default + 'static>(&mut self, tag: u32) -> &mut Self {
+        self.types.retain(|(t, _)| *t != tag);
+        self.types.push((tag, decode::<T>));
+        self
+    }
+
+    /// Decodes a tagged value, returning its tag, the value and the number of bytes read.
+    /// The value is downcast to the registered type with `Box::<dyn Any>::downcast`.
+    pub fn decode(&self, data: &[u8]) -> Result<(u32, Box<dyn Any>, usize), DecodeError> {
+        let mut dec = Decoder::new(data);
+        let mut tag = VarUint32::default();
+        dec.try_unpack(&mut tag)?;
+
+        let decode = match self.types.iter().find(|(t, _)| *t == tag.value()) {
+            Some((_, decode)) => decode,
+            None => return Err(DecodeError::BadVariant { name: "TaggedDecoder", tag: tag.value() }),
+        };
+        let pos = dec.get_pos();
+        let (value, size) = decode(&data[pos..])?;
+        Ok((tag.value(), value, pos + size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::string::String;
+    use structpacker::StructPacker;
+
+    #[derive(Clone, Debug, Eq, PartialEq, Default, StructPacker)]
+    struct Ping {
+        seq: u32,
+    }
+
+    #[derive(Clone, Debug, Eq, PartialEq, Default, StructPacker)]
+    struct Message {
+        from: u64,
+        text: String,
+    }
+
+    #[test]
+    fn test_tagged_decode() {
+        let mut decoder = TaggedDecoder::new();
+        decoder.register::<Ping>(1).register::<Message>(2);
+
+        let data = pack_tagged(1, &Ping { seq: 7 });
+        assert_eq!(data, [1, 7, 0, 0, 0]);
+        let (tag, value, size) = decoder.decode(&data).unwrap();
+        assert_eq!((tag, size), (1, data.len()));
+        assert_eq!(*value.downcast::<Ping>().unwrap(), Ping { seq: 7 });
+
+        let message = Message { from: 3, text: "hello".into() };
+        let data = pack_tagged(2, &message);
+        let (tag, value, size) = decoder.decode(&data).unwrap();
+        assert_eq!((tag, size), (2, data.len()));
+        assert!(value.downcast_ref::<Ping>().is_none());
+        assert_eq!(*value.downcast::<Message>().unwrap(), message);
+
+        let data = pack_tagged(3, &Ping { seq: 7 });
+        assert_eq!(decoder.decode(&data).err(), Some(DecodeError::BadVariant { name: "TaggedDecoder", tag: 3 }));
+        assert_eq!(decoder.decode(&[0x80]).err(), Some(DecodeError::BufferOverflow));
+    }
+
+    #[test]
+    fn test_derived_try_unpack() {
+        let message = Message { from: 3, text: "hello".into() };
+        let data = Encoder::pack(&message);
+
+        let mut value = Message::default();
+        assert_eq!(value.try_unpack(&data), Ok(data.len()));
+        assert_eq!(value, message);
+
+        // truncated in the middle of `text`
+        let mut value = Message::default();
+        assert_eq!(value.try_unpack(&data[..data.len() - 1]), Err(DecodeError::BufferOverflow));
+        // truncated in the middle of `from`
+        assert_eq!(value.try_unpack(&data[..4]), Err(DecodeError::BufferOverflow));
+    }
+}
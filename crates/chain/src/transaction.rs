@@ -7,6 +7,11 @@ use crate::serializer::{
 use crate::structs::{
     TimePointSec,
     Uint128,
+    Checksum256,
+};
+
+use crate::crypto::{
+    sha256,
 };
 
 use crate::varint::{
@@ -32,6 +37,13 @@ pub struct TransactionExtension {
     pub data:   Vec<u8>,
 }
 
+impl TransactionExtension {
+    ///
+    pub fn new(ty: u16, data: Vec<u8>) -> Self {
+        Self { ty, data }
+    }
+}
+
 impl Packer for TransactionExtension {
     fn size(&self) -> usize {
         let mut _size: usize = 0;
@@ -127,6 +139,70 @@ impl Transaction {
         return &self.extension;
     }
 
+    /// Appends a `transaction_extensions` entry, which is part of the packed transaction and so of its id.
+    /// nodeos expects the entries sorted by type.
+    pub fn add_extension(&mut self, ty: u16, data: Vec<u8>) {
+        self.extension.push(TransactionExtension::new(ty, data));
+    }
+
+    /// Sets the TaPoS fields to the block `blocks_behind` the head block of `tester`
+    /// and the expiration to `expire_sec` seconds after the head block time.
+    /// The referenced block must be the head block or one of the last blocks produced by `tester`.
+    /// The debugger server can't push a transaction built locally, so the filled transaction
+    /// can only be signed, e.g. for another chain client to push it.
+    #[cfg(feature = "std")]
+    pub fn set_tapos_from(&mut self, tester: &mut crate::ChainTester, blocks_behind: u32, expire_sec: u32) -> chaintester::client::Result<()> {
+        use chaintester::client::ChainTesterError;
+        let error = |msg: String| ChainTesterError{json: None, error_string: Some(msg)};
+
+        let info = tester.get_info()?;
+        let head_block_num = info["head_block_num"].as_u64().ok_or_else(|| error("no head_block_num in chain info".into()))?;
+        if blocks_behind as u64 >= head_block_num {
+            return Err(error(format!("can not reference block {} blocks behind head block {}", blocks_behind, head_block_num)));
+        }
+        let block_num = head_block_num - blocks_behind as u64;
+        let id = tester.get_block_id(block_num)?;
+        let id = match crate::action_trace::parse_hex(&id) {
+            Some(id) if id.len() == 32 => id,
+            _ => return Err(error(format!("invalid block id {}", id))),
+        };
+
+        // the head block time has milliseconds, e.g. 2023-05-01T12:30:00.500
+        let head_block_time = info["head_block_time"].as_str().unwrap_or_default();
+        let head_block_time = head_block_time.get(..19).and_then(|time| TimePointSec::from_iso_string(time).ok())
+            .ok_or_else(|| error(format!("invalid head_block_time {}", head_block_time)))?;
+
+        self.ref_block_num = block_num as u16;
+        self.ref_block_prefix = u32::from_le_bytes([id[8], id[9], id[10], id[11]]);
+        self.expiration = head_block_time + expire_sec;
+        Ok(())
+    }
+
+    /// Transaction id, the sha256 hash of the packed transaction.
+    ///
+    /// There is no `ChainTester` method pushing a transaction and comparing its id with this one,
+    /// the debugger server only pushes transactions it builds itself. To check the id against nodeos,
+    /// a contract can store the transaction executing it, read with `read_transaction`, and its id
+    /// can be compared with the one returned by `ChainTester::push_action`.
+    pub fn id(&self) -> Checksum256 {
+        sha256(&Encoder::pack(self))
+    }
+
+    /// Data hashed by `signing_digest`: `chain_id || packed_trx || context_free_data_digest`,
+    /// the digest of the context free data being 32 zero bytes since the transaction has none.
+    pub fn signing_data(&self, chain_id: &Checksum256) -> Vec<u8> {
+        let mut enc = Encoder::new(chain_id.size() + self.size() + 32);
+        chain_id.pack(&mut enc);
+        self.pack(&mut enc);
+        enc.alloc(32);
+        enc.get_bytes().to_vec()
+    }
+
+    /// Digest signed by the keys authorizing the transaction, as computed by cleos and nodeos
+    pub fn signing_digest(&self, chain_id: &Checksum256) -> Checksum256 {
+        sha256(&self.signing_data(chain_id))
+    }
+
     pub fn send(&self, payer: Name, id: u128, replace_existing: bool) {
         let id = Uint128{lo: (id & u64::MAX as u128) as u64, hi: (id >> 64) as u64};
         send_deferred(&id, payer, &Encoder::pack(self), replace_existing.into());
@@ -180,8 +256,108 @@ impl Packer for Transaction {
     }
 }
 
+/// Returns the chain id of `tester`, which is part of the digest signed by `Transaction::signing_digest`.
+#[cfg(feature = "std")]
+pub fn get_chain_id(tester: &mut crate::ChainTester) -> chaintester::client::Result<Checksum256> {
+    use chaintester::client::ChainTesterError;
+
+    let info = tester.get_info()?;
+    info["chain_id"].as_str().and_then(|id| id.parse::<Checksum256>().ok()).ok_or_else(|| {
+        ChainTesterError{json: Some(info.clone()), error_string: Some("invalid chain_id in chain info".into())}
+    })
+}
+
+/// Returns the ids of the transactions in block `block_num`, in execution order.
+///
+/// The debugger server has no `get_block`, so this only covers the transactions pushed by `tester` itself:
+/// transactions executed by the chain on its own, e.g. deferred transactions, are missing.
+#[cfg(feature = "std")]
+pub fn get_block_transaction_order(tester: &mut crate::ChainTester, block_num: u32) -> chaintester::client::Result<Vec<Checksum256>> {
+    use chaintester::client::ChainTesterError;
+
+    let mut ids = Vec::new();
+    for tx in tester.pushed_transactions() {
+        if tx["block_num"].as_u64() != Some(block_num as u64) {
+            continue;
+        }
+        let id = tx["id"].as_str().unwrap_or_default();
+        match id.parse::<Checksum256>() {
+            Ok(id) => ids.push(id),
+            Err(_) => return Err(ChainTesterError{json: None, error_string: Some(format!("invalid transaction id {}", id))}),
+        }
+    }
+
+    if ids.is_empty() {
+        return Err(ChainTesterError{json: None, error_string: Some(format!("no transactions pushed by this tester in block {}", block_num))});
+    }
+    Ok(ids)
+}
+
+/// Returns the account which produced the block containing transaction `id`.
+///
+/// Only transactions pushed by `tester` can be resolved, once their block has been produced by it,
+/// see `ChainTester::get_block_producer`.
+#[cfg(feature = "std")]
+pub fn transaction_producer(tester: &mut crate::ChainTester, id: &Checksum256) -> chaintester::client::Result<Name> {
+    use chaintester::client::ChainTesterError;
+
+    let tx = tester.get_transaction(&id.to_string())?;
+    let block_num = tx["block_num"].as_u64().ok_or_else(|| {
+        ChainTesterError{json: Some(tx.clone()), error_string: Some("no block_num in transaction trace".into())}
+    })?;
+    let producer = tester.get_block_producer(block_num)?;
+    Ok(Name::from_str(&producer))
+}
+
 // bool
 // check_transaction_authorization( const transaction&                 trx,
 //                                  const std::set<permission_level>&  provided_permissions ,
 //                                  const std::set<public_key>&        provided_keys = std::set<public_key>()
 //                                )
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_transaction() -> Transaction {
+        Transaction {
+            expiration: TimePointSec { seconds: 1700000000 },
+            ref_block_num: 0x1234,
+            ref_block_prefix: 0xdeadbeef,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transaction_extensions() {
+        let trx = new_transaction();
+        let packed = Encoder::pack(&trx);
+        // expiration, ref_block_num, ref_block_prefix, max_net_usage_words, max_cpu_usage_ms,
+        // delay_sec, context_free_actions, actions, transaction_extensions
+        assert_eq!(packed, [0x00, 0xf1, 0x53, 0x65, 0x34, 0x12, 0xef, 0xbe, 0xad, 0xde, 0, 0, 0, 0, 0, 0]);
+
+        let mut with_extension = new_transaction();
+        with_extension.add_extension(1, vec![0xaa, 0xbb]);
+        let packed_with_extension = Encoder::pack(&with_extension);
+        assert_eq!(packed_with_extension.len(), with_extension.size());
+        // the id is the sha256 of the packed transaction, so it differs with the extension
+        assert_eq!(packed_with_extension[..15], packed[..15]);
+        assert_eq!(packed_with_extension[15..], [1, 1, 0, 2, 0xaa, 0xbb]);
+
+        let mut unpacked = Transaction::default();
+        assert_eq!(unpacked.unpack(&packed_with_extension), packed_with_extension.len());
+        assert!(unpacked == with_extension);
+        assert!(unpacked.extension()[0] == TransactionExtension::new(1, vec![0xaa, 0xbb]));
+    }
+
+    #[test]
+    fn test_signing_data() {
+        let chain_id = Checksum256::from_hex("aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906");
+        let trx = new_transaction();
+        let data = trx.signing_data(&chain_id);
+        assert_eq!(data.len(), 32 + trx.size() + 32);
+        assert_eq!(data[..32], chain_id.data);
+        assert_eq!(data[32..48], Encoder::pack(&trx)[..]);
+        assert_eq!(data[48..], [0u8; 32]);
+    }
+}
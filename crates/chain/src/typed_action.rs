@@ -0,0 +1,16 @@
+use serde_json::Value;
+
+use chaintester::ChainTester;
+use chaintester::client::Result;
+
+use crate::serializer::{
+    Packer,
+    Encoder,
+};
+
+/// Pushes an action with `data` packed locally instead of going through the ABI of `account`.
+/// `permissions` is a json object of actor to permission, such as `{"alice": "active"}`,
+/// the same format `ChainTester::deploy_contract` builds.
+pub fn push_action_typed<T: Packer>(tester: &mut ChainTester, account: &str, action: &str, data: &T, permissions: &str) -> Result<Value> {
+    tester.push_action(account, action, Encoder::pack(data).into(), permissions)
+}
@@ -1,6 +1,7 @@
 use crate::serializer::{
     Packer,
     Encoder,
+    DecodeError,
 };
 
 use crate::print::{
@@ -30,6 +31,36 @@ impl VarUint32 {
     pub fn value(&self) -> u32 {
         return self.n;
     }
+
+    /// Returns the number of bytes of the varint at the start of `raw` without decoding it,
+    /// `None` if it is truncated or longer than `MAX_VARUINT32_SIZE` bytes.
+    pub fn peek_size(raw: &[u8]) -> Option<usize> {
+        raw.iter().take(MAX_VARUINT32_SIZE).position(|b| (*b & 0x80) == 0).map(|i| i + 1)
+    }
+}
+
+/// Maximum size of a packed `VarUint32`, 5 groups of 7 bits
+pub const MAX_VARUINT32_SIZE: usize = 5;
+
+/// Decodes the varint at the start of `data` into its value and size,
+/// rejecting encodings longer than 5 bytes and values overflowing `u32`.
+fn decode(data: &[u8]) -> Result<(u32, usize), DecodeError> {
+    let size = match VarUint32::peek_size(data) {
+        Some(size) => size,
+        None if data.len() < MAX_VARUINT32_SIZE => return Err(DecodeError::BufferOverflow),
+        None => return Err(DecodeError::Malformed),
+    };
+
+    // the 5th byte holds the 4 high bits
+    if size == MAX_VARUINT32_SIZE && data[4] > 0x0f {
+        return Err(DecodeError::Malformed);
+    }
+
+    let mut value: u32 = 0;
+    for (i, b) in data[..size].iter().enumerate() {
+        value |= (*b as u32 & 0x7f) << (7 * i);
+    }
+    Ok((value, size))
 }
 
 impl Packer for VarUint32 {
@@ -72,20 +103,26 @@ impl Packer for VarUint32 {
 
     /// Deserialize the VarUint32 value from the given byte slice.
     fn unpack(&mut self, data: &[u8]) -> usize {
-        let mut by: u32 = 0;
-        let mut value: u32 = 0;
-        let mut length: usize = 0;
-        for b in data {
-            value |= (*b as u32 & 0x7f) << by;
-            by += 7;
-            length += 1;
-            if (*b & 0x80) == 0 {
-                break;
+        match decode(data) {
+            Ok((value, size)) => {
+                self.n = value;
+                size
+            }
+            Err(DecodeError::BufferOverflow) => {
+                check(false, "varuint32: buffer overflow");
+                0
+            }
+            Err(_) => {
+                check(false, "malformed varuint32 data");
+                0
             }
-            check(by < 32, "malformed varuint32 data");
         }
+    }
+
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let (value, size) = decode(data)?;
         self.n = value;
-        return length;
+        Ok(size)
     }
 }
 
@@ -96,6 +133,82 @@ impl Printable for VarUint32 {
     }
 }
 
+/// A variable-length signed integer structure, the `varint32` ABI type.
+/// The value is zig-zag encoded into a `VarUint32`, so that small negative numbers pack compactly.
+#[cfg_attr(feature = "std", derive(crate::eosio_scale_info::TypeInfo))]
+#[derive(Copy, Clone, Eq, PartialEq, Default, Debug)]
+pub struct VarInt32 {
+    /// The signed integer value.
+    pub n: i32,
+}
+
+impl VarInt32 {
+    /// Create a new VarInt32 instance with the given value.
+    pub fn new(n: i32) -> Self {
+        Self { n: n }
+    }
+
+    /// Get the value of the VarInt32 instance.
+    pub fn value(&self) -> i32 {
+        return self.n;
+    }
+
+    fn zigzag(&self) -> VarUint32 {
+        VarUint32::new(((self.n << 1) ^ (self.n >> 31)) as u32)
+    }
+
+    fn from_zigzag(v: VarUint32) -> Self {
+        let n = v.value();
+        Self { n: ((n >> 1) as i32) ^ -((n & 1) as i32) }
+    }
+}
+
+impl From<i32> for VarInt32 {
+    fn from(n: i32) -> Self {
+        Self::new(n)
+    }
+}
+
+impl From<VarInt32> for i32 {
+    fn from(v: VarInt32) -> Self {
+        v.n
+    }
+}
+
+impl Packer for VarInt32 {
+    /// Calculate the size of the serialized VarInt32.
+    fn size(&self) -> usize {
+        self.zigzag().size()
+    }
+
+    /// Serialize the VarInt32 value.
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        self.zigzag().pack(enc)
+    }
+
+    /// Deserialize the VarInt32 value from the given byte slice.
+    fn unpack(&mut self, data: &[u8]) -> usize {
+        let mut v = VarUint32::default();
+        let size = v.unpack(data);
+        *self = Self::from_zigzag(v);
+        size
+    }
+
+    fn try_unpack(&mut self, data: &[u8]) -> Result<usize, DecodeError> {
+        let mut v = VarUint32::default();
+        let size = v.try_unpack(data)?;
+        *self = Self::from_zigzag(v);
+        Ok(size)
+    }
+}
+
+impl Printable for VarInt32 {
+    /// Print the VarInt32 value.
+    fn print(&self) {
+        printi(self.n as i64);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +242,54 @@ mod tests {
             assert_eq!(varuint32, unpacked_varuint32);
         }
     }
+
+    #[test]
+    fn test_varuint32_size_limits() {
+        let mut v = VarUint32::default();
+        assert_eq!(VarUint32::peek_size(&[0x7f, 0xff]), Some(1));
+        assert_eq!(v.try_unpack(&[0x7f, 0xff]), Ok(1));
+        assert_eq!(v.value(), 127);
+
+        let max = [0xff, 0xff, 0xff, 0xff, 0x0f];
+        assert_eq!(VarUint32::peek_size(&max), Some(5));
+        assert_eq!(v.try_unpack(&max), Ok(5));
+        assert_eq!(v.value(), u32::MAX);
+        assert_eq!(v.unpack(&max), 5);
+
+        // the 5th byte overflows u32
+        assert_eq!(VarUint32::peek_size(&[0xff, 0xff, 0xff, 0xff, 0x1f]), Some(5));
+        assert_eq!(v.try_unpack(&[0xff, 0xff, 0xff, 0xff, 0x1f]), Err(DecodeError::Malformed));
+
+        // overlong
+        let overlong = [0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+        assert_eq!(VarUint32::peek_size(&overlong), None);
+        assert_eq!(v.try_unpack(&overlong), Err(DecodeError::Malformed));
+
+        // truncated
+        assert_eq!(VarUint32::peek_size(&[0x80, 0x80]), None);
+        assert_eq!(v.try_unpack(&[0x80, 0x80]), Err(DecodeError::BufferOverflow));
+        assert_eq!(v.try_unpack(&[]), Err(DecodeError::BufferOverflow));
+    }
+
+    #[test]
+    fn test_varint32_zigzag() {
+        let cases: [(i32, &[u8]); 6] = [
+            (0, &[0]),
+            (-1, &[1]),
+            (1, &[2]),
+            (-64, &[0x7f]),
+            (i32::MAX, &[0xfe, 0xff, 0xff, 0xff, 0x0f]),
+            (i32::MIN, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+        ];
+
+        for (value, packed) in cases {
+            let v = VarInt32::from(value);
+            assert_eq!(Encoder::pack(&v), packed);
+            assert_eq!(v.size(), packed.len());
+
+            let mut unpacked = VarInt32::default();
+            assert_eq!(unpacked.try_unpack(packed), Ok(packed.len()));
+            assert_eq!(i32::from(unpacked), value);
+        }
+    }
 }
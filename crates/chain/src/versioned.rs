@@ -0,0 +1,168 @@
+use crate::serializer::{
+    Packer,
+    Encoder
+};
+
+use crate::varint::VarUint32;
+
+/// Maps the versions of a data type to their packed payloads,
+/// usually implemented by an enum with one variant per version.
+pub trait VersionedPayload: Default {
+    /// Returns the version of the payload currently held
+    fn version(&self) -> u32;
+
+    /// Returns the payload to pack after the version
+    fn payload(&self) -> &dyn Packer;
+
+    /// Unpacks a payload of `version`, returning the number of bytes read.
+    /// Implementations should `check` against versions they do not know.
+    fn unpack_payload(&mut self, version: u32, data: &[u8]) -> usize;
+}
+
+/// Packs a version as a varuint32 followed by the payload of that version
+#[derive(Default)]
+pub struct Versioned<T: VersionedPayload> {
+    value: T
+}
+
+impl<T> Versioned<T>
+where
+    T: VersionedPayload
+{
+    ///
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+        }
+    }
+
+    ///
+    pub fn version(&self) -> u32 {
+        self.value.version()
+    }
+
+    ///
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    ///
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Packer for Versioned<T>
+where
+    T: VersionedPayload
+{
+    ///
+    fn size(&self) -> usize {
+        VarUint32::new(self.version()).size() + self.value.payload().size()
+    }
+
+    ///
+    fn pack(&self, enc: &mut Encoder) -> usize {
+        let pos = enc.get_size();
+        VarUint32::new(self.version()).pack(enc);
+        self.value.payload().pack(enc);
+        enc.get_size() - pos
+    }
+
+    ///
+    fn unpack(&mut self, data: &[u8]) -> usize {
+        let mut version = VarUint32::default();
+        let size = version.unpack(data);
+        size + self.value.unpack_payload(version.value(), &data[size..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::string::String;
+    use crate::vmapi::eosio::check;
+    use structpacker::StructPacker;
+
+    #[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+    struct CounterV1 {
+        count: u32,
+    }
+
+    #[derive(Clone, Eq, PartialEq, Default, StructPacker)]
+    struct CounterV2 {
+        count: u64,
+        owner: String,
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    enum Counter {
+        V1(CounterV1),
+        V2(CounterV2),
+    }
+
+    impl Default for Counter {
+        fn default() -> Self {
+            Counter::V1(CounterV1::default())
+        }
+    }
+
+    impl VersionedPayload for Counter {
+        fn version(&self) -> u32 {
+            match self {
+                Counter::V1(_) => 1,
+                Counter::V2(_) => 2,
+            }
+        }
+
+        fn payload(&self) -> &dyn Packer {
+            match self {
+                Counter::V1(x) => x,
+                Counter::V2(x) => x,
+            }
+        }
+
+        fn unpack_payload(&mut self, version: u32, data: &[u8]) -> usize {
+            match version {
+                1 => {
+                    let mut x = CounterV1::default();
+                    let size = x.unpack(data);
+                    *self = Counter::V1(x);
+                    size
+                }
+                2 => {
+                    let mut x = CounterV2::default();
+                    let size = x.unpack(data);
+                    *self = Counter::V2(x);
+                    size
+                }
+                _ => {
+                    check(false, "unknown counter version");
+                    0
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_versioned_round_trip() {
+        let v1 = Versioned::new(Counter::V1(CounterV1 { count: 7 }));
+        let packed = Encoder::pack(&v1);
+        assert_eq!(packed, [1, 7, 0, 0, 0]);
+        assert_eq!(packed.len(), v1.size());
+
+        let mut unpacked = Versioned::<Counter>::default();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert!(unpacked.value() == v1.value());
+
+        let v2 = Versioned::new(Counter::V2(CounterV2 { count: 8, owner: "alice".into() }));
+        let packed = Encoder::pack(&v2);
+        assert_eq!(packed[0], 2);
+        assert_eq!(packed.len(), v2.size());
+
+        let mut unpacked = Versioned::<Counter>::default();
+        assert_eq!(unpacked.unpack(&packed), packed.len());
+        assert_eq!(unpacked.version(), 2);
+        assert!(unpacked.into_inner() == v2.into_inner());
+    }
+}
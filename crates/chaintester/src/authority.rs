@@ -0,0 +1,74 @@
+use serde_json::{Value};
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct PermissionLevel {
+    pub actor: String,
+    pub permission: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct KeyWeight {
+    pub key: String,
+    pub weight: u16,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct PermissionLevelWeight {
+    pub permission: PermissionLevel,
+    pub weight: u16,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct WaitWeight {
+    pub wait_sec: u32,
+    pub weight: u16,
+}
+
+/// Typed form of the `required_auth` field returned by `get_account`
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct Authority {
+    pub threshold: u32,
+    pub keys: Vec<KeyWeight>,
+    pub accounts: Vec<PermissionLevelWeight>,
+    pub waits: Vec<WaitWeight>,
+}
+
+fn parse_u32(value: &Value) -> Option<u32> {
+    value.as_u64()?.try_into().ok()
+}
+
+fn parse_u16(value: &Value) -> Option<u16> {
+    value.as_u64()?.try_into().ok()
+}
+
+impl Authority {
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let mut ret = Authority::default();
+        ret.threshold = parse_u32(&value["threshold"])?;
+
+        for key in value["keys"].as_array()? {
+            ret.keys.push(KeyWeight {
+                key: key["key"].as_str()?.into(),
+                weight: parse_u16(&key["weight"])?,
+            });
+        }
+
+        for account in value["accounts"].as_array()? {
+            ret.accounts.push(PermissionLevelWeight {
+                permission: PermissionLevel {
+                    actor: account["permission"]["actor"].as_str()?.into(),
+                    permission: account["permission"]["permission"].as_str()?.into(),
+                },
+                weight: parse_u16(&account["weight"])?,
+            });
+        }
+
+        for wait in value["waits"].as_array()? {
+            ret.waits.push(WaitWeight {
+                wait_sec: parse_u32(&wait["wait_sec"])?,
+                weight: parse_u16(&wait["weight"])?,
+            });
+        }
+        Some(ret)
+    }
+}
@@ -2,16 +2,25 @@ use std::fmt;
 use std::panic;
 
 use std::{fs};
-use std::{thread, time::Duration};
+use std::{thread, time::{Duration, Instant}};
+use std::net::{TcpStream, ToSocketAddrs};
 use std::ops::{Deref, DerefMut};
-use std::collections::{HashMap};
+use std::collections::{HashMap, BTreeMap};
+use serde::de::DeserializeOwned;
 use serde_json::{Value};
+use sha2::{Digest, Sha256};
 
 use thrift::protocol::{TBinaryInputProtocol, TBinaryOutputProtocol};
 use thrift::transport::{
     ReadHalf, TBufferedReadTransport, TBufferedWriteTransport, TIoChannel, TTcpChannel, WriteHalf,
 };
 
+use crate::authority::{
+    Authority,
+};
+
+use crate::VmApiClientConfig;
+
 use crate::interfaces::{
     IPCChainTesterSyncClient,
     TIPCChainTesterSyncClient,
@@ -27,7 +36,10 @@ type ClientOutputProtocol = TBinaryOutputProtocol<TBufferedWriteTransport<WriteH
 use std::convert::{From, Into, TryInto};
 
 use lazy_static::lazy_static; // 1.4.0
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{
+    Arc,
+    Condvar,
     Mutex,
     MutexGuard
 };
@@ -64,10 +76,40 @@ impl ChainTesterError {
         return None;
     }
 
+    /// Returns an error describing the mismatch unless the assertion message of the exception is `err`
+    pub fn expect_err(&self, err: &str) -> std::result::Result<(), String> {
+        let err2 = match self.json {
+            Some(_) => self.get_err(),
+            None => None,
+        };
+        match err2 {
+            Some(err2) if err2 == err => Ok(()),
+            Some(err2) => Err(format!("invalid error, expect {}, got {}", err, err2)),
+            None => Err(format!("invalid error, expect {}, got an error without message: {}", err, self)),
+        }
+    }
+
     pub fn check_err(&self, err: &str) {
-        let err2 = &self.get_err().unwrap() ;
-        if err2 != err {
-            panic!("invalid error, expect {}, got {}", err, err2);
+        if let Err(msg) = self.expect_err(err) {
+            panic!("{}", msg);
+        }
+    }
+
+    /// Antelope error code of the exception, e.g. 3050003 for `eosio_assert_message_exception`
+    pub fn error_code(&self) -> Option<i64> {
+        self.json.as_ref()?["except"]["code"].as_i64()
+    }
+
+    /// Name of the exception, e.g. `eosio_assert_message_exception`
+    pub fn error_name(&self) -> Option<String> {
+        self.json.as_ref()?["except"]["name"].as_str().map(String::from)
+    }
+
+    pub fn assert_code(&self, code: i64) {
+        match self.error_code() {
+            Some(code2) if code2 == code => {}
+            Some(code2) => panic!("invalid error code, expect {}, got {} ({})", code, code2, self.error_name().unwrap_or_default()),
+            None => panic!("invalid error, expect code {}, got {}", code, self),
         }
     }
 }
@@ -100,10 +142,119 @@ impl fmt::Debug for ChainTesterError {
     }
 }
 
+/// Returned by `ChainTester::push_action_with_deadline`
+pub struct DeadlineReturn {
+    /// result of the action pushed under the cpu limit
+    pub result: Result<Value>,
+    /// result of restoring the blockchain parameters, the cpu limit stays in effect on error
+    pub restore: Result<()>,
+}
+
 pub struct TransactionReturn {
     pub value: Value
 }
 
+impl TransactionReturn {
+    pub fn new(value: Value) -> Self {
+        Self { value }
+    }
+
+    /// Number of inline actions sent while executing the transaction.
+    /// Notifications created by `require_recipient` are not counted.
+    pub fn inline_action_count(&self) -> usize {
+        let traces = match self.value["action_traces"].as_array() {
+            Some(traces) => traces,
+            None => return 0,
+        };
+
+        traces.iter().filter(|trace| {
+            let creator_action_ordinal = trace["creator_action_ordinal"].as_u64().unwrap_or(0);
+            creator_action_ordinal != 0 && trace["receiver"] == trace["act"]["account"]
+        }).count()
+    }
+
+    pub fn assert_no_inline_actions(&self) {
+        let count = self.inline_action_count();
+        if count != 0 {
+            panic!("expect no inline actions, got {}", count);
+        }
+    }
+
+    /// Billed cpu time of the transaction, in microseconds
+    pub fn cpu_usage_us(&self) -> u64 {
+        self.value["receipt"]["cpu_usage_us"].as_u64().unwrap_or(0)
+    }
+
+    /// Billed net usage of the transaction, in 8 bytes words
+    pub fn net_usage_words(&self) -> u64 {
+        self.value["receipt"]["net_usage_words"].as_u64().unwrap_or(0)
+    }
+
+    /// Panics if the cpu usage differs from `baseline_us` by more than `tolerance_pct` percent
+    pub fn assert_cpu_within(&self, baseline_us: u64, tolerance_pct: f64) {
+        assert_usage_within("cpu usage", self.cpu_usage_us(), baseline_us, tolerance_pct);
+    }
+
+    /// RAM deltas of the transaction, with the action that caused each of them
+    pub fn ram_deltas(&self) -> Vec<RamDelta> {
+        let traces = match self.value["action_traces"].as_array() {
+            Some(traces) => traces,
+            None => return Vec::new(),
+        };
+
+        let mut deltas = Vec::new();
+        for trace in traces {
+            for delta in trace["account_ram_deltas"].as_array().into_iter().flatten() {
+                deltas.push(RamDelta {
+                    account: delta["account"].as_str().unwrap_or_default().into(),
+                    delta: delta["delta"].as_i64().unwrap_or(0),
+                    action_ordinal: trace["action_ordinal"].as_u64().unwrap_or(0),
+                    receiver: trace["receiver"].as_str().unwrap_or_default().into(),
+                    action_account: trace["act"]["account"].as_str().unwrap_or_default().into(),
+                    action_name: trace["act"]["name"].as_str().unwrap_or_default().into(),
+                });
+            }
+        }
+        deltas
+    }
+
+    /// Sum of the RAM deltas of `account` in the transaction
+    pub fn ram_delta_of(&self, account: &str) -> i64 {
+        self.ram_deltas().iter().filter(|delta| delta.account == account).map(|delta| delta.delta).sum()
+    }
+
+    /// Panics if the net usage differs from `baseline_words` by more than `tolerance_pct` percent
+    pub fn assert_net_within(&self, baseline_words: u64, tolerance_pct: f64) {
+        assert_usage_within("net usage", self.net_usage_words(), baseline_words, tolerance_pct);
+    }
+}
+
+/// A change of the RAM usage of `account`, caused by the action at `action_ordinal`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RamDelta {
+    pub account: String,
+    pub delta: i64,
+    pub action_ordinal: u64,
+    /// the account whose code was executing, differs from `action_account` in notifications
+    pub receiver: String,
+    pub action_account: String,
+    pub action_name: String,
+}
+
+fn assert_usage_within(resource: &str, usage: u64, baseline: u64, tolerance_pct: f64) {
+    let tolerance = baseline as f64 * tolerance_pct / 100.0;
+    let diff = (usage as f64 - baseline as f64).abs();
+    if diff > tolerance {
+        panic!("{} {} is not within {}% of {}", resource, usage, tolerance_pct, baseline);
+    }
+}
+
+impl From<Value> for TransactionReturn {
+    fn from(value: Value) -> Self {
+        Self::new(value)
+    }
+}
+
 impl fmt::Display for TransactionReturn {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", serde_json::to_string_pretty(&self.value).unwrap())
@@ -118,6 +269,47 @@ impl fmt::Debug for TransactionReturn {
 
 pub type Result<T> = core::result::Result<T, ChainTesterError>;
 
+/// An action delivered to `receiver` through `require_recipient`
+pub struct NotificationTrace {
+    pub receiver: String,
+    pub account: String,
+    pub action: String,
+    pub action_ordinal: u64,
+    pub console: String,
+    /// inline actions sent by the notification handler
+    pub inline_actions: Vec<Value>,
+    pub trace: Value,
+}
+
+impl NotificationTrace {
+    pub fn parse(tx: &Value) -> Vec<NotificationTrace> {
+        let traces = match tx["action_traces"].as_array() {
+            Some(traces) => traces,
+            None => return Vec::new(),
+        };
+
+        traces.iter().filter(|trace| {
+            trace["receiver"] != trace["act"]["account"]
+        }).map(|trace| {
+            let action_ordinal = trace["action_ordinal"].as_u64().unwrap_or(0);
+            let inline_actions = traces.iter().filter(|inline| {
+                inline["creator_action_ordinal"].as_u64() == Some(action_ordinal)
+                    && inline["receiver"] == inline["act"]["account"]
+            }).cloned().collect();
+
+            NotificationTrace {
+                receiver: trace["receiver"].as_str().unwrap_or_default().into(),
+                account: trace["act"]["account"].as_str().unwrap_or_default().into(),
+                action: trace["act"]["name"].as_str().unwrap_or_default().into(),
+                action_ordinal,
+                console: trace["console"].as_str().unwrap_or_default().into(),
+                inline_actions,
+                trace: trace.clone(),
+            }
+        }).collect()
+    }
+}
+
 pub struct GetTableRowsPrams<'a> {
     pub json: bool,
     pub code: &'a str,
@@ -152,13 +344,189 @@ impl<'a> Default for GetTableRowsPrams<'a> {
     }
 }
 
+impl<'a> GetTableRowsPrams<'a> {
+    /// Starts building parameters from the defaults, e.g.
+    /// `GetTableRowsPrams::builder().code("eosio.token").scope("alice").table("accounts").build()`
+    pub fn builder() -> GetTableRowsPramsBuilder<'a> {
+        GetTableRowsPramsBuilder { params: Self::default() }
+    }
+}
+
+/// Bounds of a `name` key, kept as the decimal strings of the name values so that
+/// `GetTableRowsPramsBuilder::name_bounds` can borrow them, e.g.
+/// `NameBounds::new(s2n("alice"), s2n("bob"))`
+pub struct NameBounds {
+    lower: String,
+    upper: String,
+}
+
+impl NameBounds {
+    /// Bounds from name values such as `Name::new("alice")` or `s2n("alice")`, both inclusive
+    pub fn new<N: Into<u64>>(lower: N, upper: N) -> Self {
+        let (lower, upper): (u64, u64) = (lower.into(), upper.into());
+        Self { lower: lower.to_string(), upper: upper.to_string() }
+    }
+}
+
+/// Builder of `GetTableRowsPrams` returned by `GetTableRowsPrams::builder`
+pub struct GetTableRowsPramsBuilder<'a> {
+    params: GetTableRowsPrams<'a>,
+}
+
+impl<'a> GetTableRowsPramsBuilder<'a> {
+    ///
+    pub fn json(mut self, json: bool) -> Self {
+        self.params.json = json;
+        self
+    }
+
+    ///
+    pub fn code(mut self, code: &'a str) -> Self {
+        self.params.code = code;
+        self
+    }
+
+    ///
+    pub fn scope(mut self, scope: &'a str) -> Self {
+        self.params.scope = scope;
+        self
+    }
+
+    ///
+    pub fn table(mut self, table: &'a str) -> Self {
+        self.params.table = table;
+        self
+    }
+
+    ///
+    pub fn lower_bound(mut self, lower_bound: &'a str) -> Self {
+        self.params.lower_bound = lower_bound;
+        self
+    }
+
+    ///
+    pub fn upper_bound(mut self, upper_bound: &'a str) -> Self {
+        self.params.upper_bound = upper_bound;
+        self
+    }
+
+    /// Queries the `name` keys within `bounds`, see `NameBounds`
+    pub fn name_bounds(mut self, bounds: &'a NameBounds) -> Self {
+        self.params.key_type = "i64";
+        self.params.encode_type = "dec";
+        self.params.lower_bound = &bounds.lower;
+        self.params.upper_bound = &bounds.upper;
+        self
+    }
+
+    ///
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.params.limit = limit;
+        self
+    }
+
+    ///
+    pub fn key_type(mut self, key_type: &'a str) -> Self {
+        self.params.key_type = key_type;
+        self
+    }
+
+    ///
+    pub fn index_position(mut self, index_position: &'a str) -> Self {
+        self.params.index_position = index_position;
+        self
+    }
+
+    ///
+    pub fn encode_type(mut self, encode_type: &'a str) -> Self {
+        self.params.encode_type = encode_type;
+        self
+    }
+
+    ///
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.params.reverse = reverse;
+        self
+    }
+
+    ///
+    pub fn show_payer(mut self, show_payer: bool) -> Self {
+        self.params.show_payer = show_payer;
+        self
+    }
+
+    ///
+    pub fn build(self) -> GetTableRowsPrams<'a> {
+        self.params
+    }
+}
+
+/// Rows returned by `decode_table_rows`
+pub struct TableRows<T> {
+    pub rows: Vec<T>,
+    pub more: bool,
+    /// lower bound of the next page when `more` is true
+    pub next_key: String,
+}
+
+/// Returns the data of a row returned by `get_table_rows_ex`, a json object or a hex string,
+/// rows are `{"data": ..., "payer": ...}` objects when `show_payer` is set
+pub fn table_row_data(row: &Value) -> &Value {
+    match row.get("data") {
+        Some(data) if row.get("payer").is_some() => data,
+        _ => row,
+    }
+}
+
+/// Decodes the json form of a row
+pub fn decode_json_row<T: DeserializeOwned>(data: &Value) -> std::result::Result<T, String> {
+    serde_json::from_value(data.clone()).map_err(|err| err.to_string())
+}
+
+/// Decodes the rows returned by `get_table_rows_ex` with `decode`, which is given the data of each row
+pub fn decode_table_rows<T, F>(ret: &Value, decode: F) -> Result<TableRows<T>>
+where
+    F: Fn(&Value) -> std::result::Result<T, String>,
+{
+    let mut rows = Vec::new();
+    for row in ret["rows"].as_array().into_iter().flatten() {
+        let value = decode(table_row_data(row)).map_err(|err| {
+            ChainTesterError{json: Some(row.clone()), error_string: Some(format!("can not decode row: {}", err))}
+        })?;
+        rows.push(value);
+    }
+
+    Ok(TableRows {
+        rows,
+        more: ret["more"].as_bool().unwrap_or(false),
+        next_key: ret["next_key"].as_str().unwrap_or_default().into(),
+    })
+}
+
+pub struct ContractInfo {
+    /// hex encoded sha256 hash of the contract code
+    pub code_hash: String,
+    pub abi_version: String,
+    pub actions: Vec<String>,
+    pub tables: Vec<String>,
+}
+
 pub struct VMAPIClient {
     vm_api_client: Option<ApplySyncClient<ClientInputProtocol, ClientOutputProtocol>>,
     in_apply: bool,
+    apply_started: Option<Instant>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClientStatus {
+    Uninitialized,
+    Running,
+    Closed,
 }
 
 pub struct ChainTesterClient {
     client: Option<IPCChainTesterSyncClient<ClientInputProtocol, ClientOutputProtocol>>,
+    status: ClientStatus,
 }
 
 lazy_static! {
@@ -173,12 +541,127 @@ lazy_static! {
     static ref TEST_MUTEX: Mutex<i32> = Mutex::new(0);
 }
 
+/// Native apply handler, called with `(receiver, first_receiver, action)`.
+/// `first_receiver` is the account the action was sent to (the `code` of eosio.cdt),
+/// `receiver` differs from it when the action is delivered as a notification.
 type FnApply = fn(u64, u64, u64);
 
+/// Native apply handler which can capture state, called like `FnApply`
+pub type BoxedApply = Box<dyn Fn(u64, u64, u64) + Send>;
+
 lazy_static! {
     static ref CHAIN_TESTER_APPLYS: Mutex<HashMap<i32, HashMap<String, FnApply>>> = Mutex::new(HashMap::new());
 }
 
+lazy_static! {
+    static ref CHAIN_TESTER_BOXED_APPLYS: Mutex<HashMap<(i32, String), BoxedApply>> = Mutex::new(HashMap::new());
+}
+
+struct ChainLimit {
+    live: usize,
+    /// 0 means unlimited
+    max: usize,
+    /// how long `ChainTester::new` waits for a free slot
+    timeout: Duration,
+}
+
+/// Time `ChainTester::new` waits for another chain to be freed when the limit of `set_max_chain_count` is reached
+pub const DEFAULT_CHAIN_SLOT_TIMEOUT: Duration = Duration::from_secs(60);
+
+lazy_static! {
+    static ref CHAIN_LIMIT: (Mutex<ChainLimit>, Condvar) = (Mutex::new(ChainLimit{live: 0, max: 0, timeout: DEFAULT_CHAIN_SLOT_TIMEOUT}), Condvar::new());
+}
+
+/// Limits the number of chains alive at the same time, `ChainTester::new` blocks until
+/// another chain is freed when the limit is reached. 0 means unlimited, which is the default.
+pub fn set_max_chain_count(max: usize) {
+    set_max_chain_count_ex(max, DEFAULT_CHAIN_SLOT_TIMEOUT)
+}
+
+/// Same as `set_max_chain_count`, but `ChainTester::new` fails when no chain is freed within `timeout`,
+/// e.g. because a tester was leaked.
+pub fn set_max_chain_count_ex(max: usize, timeout: Duration) {
+    let (limit, cvar) = &*CHAIN_LIMIT;
+    let mut limit = limit.lock().unwrap();
+    limit.max = max;
+    limit.timeout = timeout;
+    cvar.notify_all();
+}
+
+fn acquire_chain_slot() -> Result<()> {
+    let (limit, cvar) = &*CHAIN_LIMIT;
+    let limit = limit.lock().unwrap();
+    let timeout = limit.timeout;
+    let (mut limit, ret) = cvar.wait_timeout_while(limit, timeout, |limit| limit.max != 0 && limit.live >= limit.max).unwrap();
+    if ret.timed_out() {
+        return Err(ChainTesterError{
+            json: None, error_string: Some(format!("no chain freed within {:?}, {} of at most {} chains are alive", timeout, limit.live, limit.max)),
+        });
+    }
+    limit.live += 1;
+    Ok(())
+}
+
+fn release_chain_slot() {
+    let (limit, cvar) = &*CHAIN_LIMIT;
+    limit.lock().unwrap().live -= 1;
+    cvar.notify_one();
+}
+
+type ChainHook = Arc<dyn Fn(&mut ChainTester) + Send + Sync>;
+
+#[derive(Default)]
+struct ChainHooks {
+    init: Vec<ChainHook>,
+    teardown: Vec<ChainHook>,
+}
+
+lazy_static! {
+    static ref CHAIN_HOOKS: Mutex<ChainHooks> = Mutex::new(ChainHooks::default());
+}
+
+/// Registers a hook called on every chain created by `ChainTester::new`/`new_ex`,
+/// in registration order.
+pub fn register_chain_init_hook(hook: Box<dyn Fn(&mut ChainTester) + Send + Sync>) {
+    CHAIN_HOOKS.lock().unwrap().init.push(Arc::from(hook));
+}
+
+/// Registers a hook called by `ChainTester::free` before the chain is freed,
+/// in registration order.
+pub fn register_chain_teardown_hook(hook: Box<dyn Fn(&mut ChainTester) + Send + Sync>) {
+    CHAIN_HOOKS.lock().unwrap().teardown.push(Arc::from(hook));
+}
+
+fn run_chain_hooks(tester: &mut ChainTester, init: bool) {
+    // hooks are cloned so that they can create chains themselves
+    let hooks = {
+        let hooks = CHAIN_HOOKS.lock().unwrap();
+        if init { hooks.init.clone() } else { hooks.teardown.clone() }
+    };
+    for hook in hooks {
+        hook(tester);
+    }
+}
+
+lazy_static! {
+    static ref CHAIN_CREATION_STATS: Mutex<(u64, u64)> = Mutex::new((0, 0));
+}
+
+/// Returns the number of chains created and the total milliseconds spent creating them,
+/// only recorded while debug mode is enabled with `get_globals().set_debug_mode(true)`.
+pub fn chain_creation_stats() -> (u64, u64) {
+    *CHAIN_CREATION_STATS.lock().unwrap()
+}
+
+fn record_chain_creation(start: Instant) {
+    if !get_globals().get_debug_mode() {
+        return;
+    }
+    let mut stats = CHAIN_CREATION_STATS.lock().unwrap();
+    stats.0 += 1;
+    stats.1 += start.elapsed().as_millis() as u64;
+}
+
 pub fn get_test_mutex() -> MutexGuard<'static, i32> {
     let ret = TEST_MUTEX.lock().unwrap();
     return ret;
@@ -189,6 +672,10 @@ pub fn get_apply_map_mutex() -> MutexGuard<'static, HashMap<i32, HashMap<String,
     return ret;
 }
 
+pub(crate) fn get_boxed_apply_map_mutex() -> MutexGuard<'static, HashMap<(i32, String), BoxedApply>> {
+    CHAIN_TESTER_BOXED_APPLYS.lock().unwrap()
+}
+
 pub struct GlobalVariables {
     pub current_test_case: String,
     pub debug_mode: bool,
@@ -225,10 +712,15 @@ pub fn get_globals() -> MutexGuard<'static, GlobalVariables> {
 }
 
 pub fn init_vm_api_client() {
+    try_init_vm_api_client().unwrap();
+}
+
+fn try_init_vm_api_client() -> thrift::Result<()> {
     let mut ret = VM_API_CLIENT.lock().unwrap();
     if ret.vm_api_client.is_none() {
-        ret.init();
+        ret.try_init()?;
     }
+    Ok(())
 }
 
 pub fn get_vm_api_client() -> MutexGuard<'static, VMAPIClient> {
@@ -246,20 +738,37 @@ pub fn close_vm_api_client() {
 
 impl VMAPIClient {
     fn new() -> Self {
-        VMAPIClient{vm_api_client: None, in_apply: false}
+        VMAPIClient{vm_api_client: None, in_apply: false, apply_started: None}
     }
 
     pub fn init(&mut self) {
+        self.try_init().unwrap();
+    }
+
+    fn try_init(&mut self) -> thrift::Result<()> {
         if self.vm_api_client.is_none() {
             let host = crate::get_debugger_config().vm_api_server_address.clone();
             let port = crate::get_debugger_config().vm_api_server_port;
-            let client = new_vm_api_client(&host, port).unwrap();
+            let client = new_vm_api_client(&host, port)?;
             self.vm_api_client = Some(client);
         }
+        Ok(())
     }
 
     pub fn set_in_apply(&mut self, in_apply: bool) {
         self.in_apply = in_apply;
+        self.apply_started = if in_apply { Some(Instant::now()) } else { None };
+    }
+
+    /// Returns how long the apply context has been active if it is longer than `max`,
+    /// which usually means that it has leaked.
+    pub fn stuck_apply_duration(&self, max: Duration) -> Option<Duration> {
+        let elapsed = self.apply_started?.elapsed();
+        if self.in_apply && elapsed > max {
+            Some(elapsed)
+        } else {
+            None
+        }
     }
 
     pub fn is_in_apply(&mut self) -> bool {
@@ -297,54 +806,103 @@ impl DerefMut for VMAPIClient {
 }
 // 
 
+/// Background thread reporting an apply context active for too long, stopped when dropped.
+pub struct ApplyWatchdog {
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+/// Starts a watchdog calling `report` once for each apply context active for longer than `max`.
+pub fn start_apply_watchdog(max: Duration, report: fn(Duration)) -> ApplyWatchdog {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let interval = std::cmp::max(max / 2, Duration::from_millis(10));
+    let thread = thread::spawn(move || {
+        let mut reported: Option<Instant> = None;
+        while !thread_stop.load(Ordering::SeqCst) {
+            let (started, stuck) = {
+                let client = VM_API_CLIENT.lock().unwrap_or_else(|err| err.into_inner());
+                (client.apply_started, client.stuck_apply_duration(max))
+            };
+            if let Some(elapsed) = stuck {
+                if reported != started {
+                    reported = started;
+                    report(elapsed);
+                }
+            }
+            thread::sleep(interval);
+        }
+    });
+    ApplyWatchdog{stop, thread: Some(thread)}
+}
+
+impl Drop for ApplyWatchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
 impl ChainTesterClient {
     fn new() -> Self {
         // better_panic::install();
-        ChainTesterClient{client: None}
+        ChainTesterClient{client: None, status: ClientStatus::Uninitialized}
     }
 
-    fn init(&mut self) {
-        if self.client.is_some() {
-            return;
+    /// Connects to the debugger server, on failure everything opened so far is closed
+    /// so that the next call starts from scratch.
+    fn init(&mut self) -> thrift::Result<()> {
+        if self.status == ClientStatus::Running {
+            return Ok(());
+        }
+
+        let ret = self.try_init();
+        if ret.is_err() {
+            self.client = None;
+            close_vm_api_client();
+            crate::server::close_apply_request_server();
         }
+        ret
+    }
 
+    fn try_init(&mut self) -> thrift::Result<()> {
         let host = crate::get_debugger_config().debugger_server_address.clone();
         let port = crate::get_debugger_config().debugger_server_port;
 
-        let mut c = TTcpChannel::new();
-    
+        let rpc_timeout = crate::get_debugger_config().rpc_timeout;
+
         // open the underlying TCP stream
         println!("connecting to debugger server on {}:{}", host, port);
-        c.open(&format!("{}:{}", host, port)).unwrap();    
+        let c = open_tcp_channel(&format!("{}:{}", host, port), None, rpc_timeout)?;
         println!("debugger server connected");
-        
-        // clone the TCP channel into two halves, one which
-        // we'll use for reading, the other for writing
-        let (i_chan, o_chan) = c.split().unwrap();
-    
-        // wrap the raw sockets (slow) with a buffered transport of some kind
-        let i_tran = TBufferedReadTransport::new(i_chan);
-        let o_tran = TBufferedWriteTransport::new(o_chan);
-    
-        // now create the protocol implementations
-        let i_prot = TBinaryInputProtocol::new(i_tran, false);
-        let o_prot = TBinaryOutputProtocol::new(o_tran, true);
-    
+
+        let (i_prot, o_prot) = new_client_protocols(c)?;
         let mut client = IPCChainTesterSyncClient::new(i_prot, o_prot);
-        client.init_vm_api().unwrap();
-        init_vm_api_client(); //init vm api client
+        client.init_vm_api()?;
+        try_init_vm_api_client()?; //init vm api client
 
-        client.init_apply_request().unwrap();
-        crate::server::init_apply_request_server(); //init apply request server
+        client.init_apply_request()?;
+        crate::server::try_init_apply_request_server()?; //init apply request server
 
         self.client = Some(client);
-
+        self.status = ClientStatus::Running;
+        Ok(())
     }
 
     pub fn close(&mut self) {
-        if self.client.is_some() {
-            self.client = None;
+        if self.status != ClientStatus::Running {
+            return;
         }
+        self.client = None;
+        close_vm_api_client();
+        crate::server::close_apply_request_server();
+        self.status = ClientStatus::Closed;
+    }
+
+    pub fn status(&self) -> ClientStatus {
+        return self.status;
     }
 }
 
@@ -364,11 +922,19 @@ impl DerefMut for ChainTesterClient {
 }
 
 pub fn get_chain_tester_client() -> MutexGuard<'static, ChainTesterClient> {
+    // the lock is released on failure, so the mutex is not poisoned for the next attempt
+    match try_get_chain_tester_client() {
+        Ok(ret) => ret,
+        Err(err) => panic!("failed to connect to debugger server: {:?}", err),
+    }
+}
+
+fn try_get_chain_tester_client() -> thrift::Result<MutexGuard<'static, ChainTesterClient>> {
     let mut ret = CHAIN_TESTER_CLIENT.lock().unwrap();
     if ret.client.is_none() {
-        ret.init();
+        ret.init()?;
     }
-    return ret;
+    Ok(ret)
 }
 
 pub fn close_chain_tester_client() {
@@ -376,10 +942,61 @@ pub fn close_chain_tester_client() {
     ret.close();
 }
 
+/// Returns the status of the connection to the debugger server without connecting to it.
+pub fn get_chain_tester_status() -> ClientStatus {
+    CHAIN_TESTER_CLIENT.lock().unwrap().status()
+}
+
+/// Blocks produced by `ChainTester::produce_until` before giving up
+pub const DEFAULT_PRODUCE_UNTIL_MAX_BLOCKS: u32 = 100;
+
+/// Produced blocks whose producer and id are remembered by `ChainTester`, older ones are forgotten
+pub const MAX_RECORDED_BLOCKS: usize = 1024;
+
+struct ProducedBlock {
+    producer: String,
+    id: String,
+}
+
 pub struct ChainTester {
     id: i32,
+    /// ABIs and code hashes deployed through this tester, the debugger server can't return them
+    deployed_abis: HashMap<String, Value>,
+    code_hashes: HashMap<String, String>,
+    transactions: HashMap<String, Value>,
+    transaction_ids: Vec<String>,
+    /// the last `MAX_RECORDED_BLOCKS` blocks produced by this tester
+    produced_blocks: BTreeMap<u64, ProducedBlock>,
+    /// measured by `get_block_interval_ms`
+    block_interval_ms: Option<i64>,
+    spans: Vec<(String, Duration)>,
+    span_stack: Vec<String>,
+    freed: bool,
 }
 
+const MAX_MEMO_SIZE: usize = 256;
+
+/// Fields of the `blockchain_parameters` taken by `eosio::setparams`, as kept in the `global` table of eosio
+const BLOCKCHAIN_PARAMETERS: [&str; 17] = [
+    "max_block_net_usage",
+    "target_block_net_usage_pct",
+    "max_transaction_net_usage",
+    "base_per_transaction_net_usage",
+    "net_usage_leeway",
+    "context_free_discount_net_usage_num",
+    "context_free_discount_net_usage_den",
+    "max_block_cpu_usage",
+    "target_block_cpu_usage_pct",
+    "max_transaction_cpu_usage",
+    "min_transaction_cpu_usage",
+    "max_transaction_lifetime",
+    "deferred_trx_expiration_window",
+    "max_transaction_delay",
+    "max_inline_action_size",
+    "max_inline_action_depth",
+    "max_authority_depth",
+];
+
 fn parse_ret(ret: &thrift::Result<String>) -> Result<Value> {
     match ret {
         Ok(ret) => {
@@ -425,78 +1042,349 @@ fn parse_ret2(ret: &thrift::Result<Vec<u8>>) -> Result<Value> {
 
 impl ChainTester {
     pub fn new() -> Self {
-        let id = get_chain_tester_client().new_chain(true).unwrap();
-        get_apply_map_mutex().insert(id, HashMap::new());
-        Self { id }
+        Self::try_new().unwrap()
     }
 
     pub fn new_ex(initialize: bool) -> Self {
-        Self { id: get_chain_tester_client().new_chain(initialize).unwrap() }
+        Self::try_new_ex(initialize).unwrap()
     }
 
-    fn client(&mut self) -> MutexGuard<'static, ChainTesterClient> {
-        get_chain_tester_client()
+    /// Same as `new`, but returns an error instead of panicking when the chain can't be created,
+    /// usually because the debugger server is not running.
+    pub fn try_new() -> Result<Self> {
+        Self::try_new_ex(true)
     }
 
-    pub fn free(&mut self) {
-        get_apply_map_mutex().remove(&self.id);
-        self.client().free_chain(self.id).unwrap();
+    pub fn try_new_ex(initialize: bool) -> Result<Self> {
+        let start = Instant::now();
+        acquire_chain_slot()?;
+        let id = match try_get_chain_tester_client().and_then(|mut client| client.new_chain(initialize)) {
+            Ok(id) => id,
+            Err(err) => {
+                release_chain_slot();
+                return Err(ChainTesterError{
+                    json: None,
+                    error_string: Some(format!("can not create chain, is the chaintester server running? {}", err)),
+                });
+            }
+        };
+        get_apply_map_mutex().insert(id, HashMap::new());
+        let mut tester = Self::with_id(id);
+        run_chain_hooks(&mut tester, true);
+        record_chain_creation(start);
+        Ok(tester)
     }
 
-    pub fn produce_block(&mut self) {
-        self.client().produce_block(self.id, 0).unwrap()
+    /// Wraps the existing chain `id`, usually returned by `leak`, the chain is freed on drop as usual.
+    pub fn attach(id: i32) -> Result<Self> {
+        acquire_chain_slot()?;
+        let ret = get_chain_tester_client().get_info(id);
+        if let Err(err) = parse_ret(&ret) {
+            release_chain_slot();
+            return Err(err);
+        }
+        get_apply_map_mutex().entry(id).or_insert_with(HashMap::new);
+        Ok(Self::with_id(id))
     }
 
-    pub fn produce_block_ex(&mut self, next_block_skip_seconds: i64) {
-        self.client().produce_block(self.id, next_block_skip_seconds).unwrap()
+    /// Releases the tester without freeing its chain and returns the chain id, see `attach`.
+    pub fn leak(mut self) -> i32 {
+        self.freed = true;
+        release_chain_slot();
+        self.id
     }
 
-    pub fn enable_debugging(&mut self, enable: bool) -> thrift::Result<()> {
-        self.client().enable_debugging(enable)
+    pub fn id(&self) -> i32 {
+        self.id
     }
 
-    pub fn set_native_contract(&mut self, contract: &str, dylib: &str) -> thrift::Result<bool> {
-        self.client().set_native_contract(self.id, contract.into(), dylib.into())
+    /// Runs `f` with a fresh `ChainTester`, the chain is freed when `f` returns or panics.
+    pub fn with<F, R>(f: F) -> R
+    where F: FnOnce(&mut ChainTester) -> R
+    {
+        let mut tester = ChainTester::new();
+        f(&mut tester)
     }
 
-    pub fn set_native_apply(&mut self, contract: &str, apply: Option<FnApply>) -> thrift::Result<()> {
-        let tester_apply_map = &mut get_apply_map_mutex();
-        let apply_map = tester_apply_map.get_mut(&self.id).unwrap();
-        if let Some(_apply) = apply {
-            apply_map.insert(contract.into(), _apply);
-            self.enable_debug_contract(contract, true)?;
-        } else {
-            let _contract: String = contract.into();
-            apply_map.remove(&_contract);
-            self.enable_debug_contract(contract, false)?;
+    fn with_id(id: i32) -> Self {
+        Self {
+            id,
+            deployed_abis: HashMap::new(),
+            code_hashes: HashMap::new(),
+            transactions: HashMap::new(),
+            transaction_ids: Vec::new(),
+            produced_blocks: BTreeMap::new(),
+            block_interval_ms: None,
+            spans: Vec::new(),
+            span_stack: Vec::new(),
+            freed: false,
         }
-        Ok(())
     }
 
-    pub fn enable_debug_contract(&mut self, contract: &str, enable: bool) -> thrift::Result<()> {
-        self.client().enable_debug_contract(self.id, contract.into(), enable)
+    fn client(&mut self) -> MutexGuard<'static, ChainTesterClient> {
+        get_chain_tester_client()
     }
 
-    pub fn is_debug_contract_enabled(&mut self, contract: &str) -> thrift::Result<bool> {
-        self.client().is_debug_contract_enabled(self.id, contract.into())
+    /// Runs `f` and adds its duration to the span `name` of the timing report.
+    /// Spans can be nested, a nested span is reported as `outer/inner`.
+    pub fn with_span<F, R>(&mut self, name: &str, f: F) -> R
+    where F: FnOnce(&mut ChainTester) -> R
+    {
+        self.span_stack.push(name.into());
+        let path = self.span_stack.join("/");
+        let start = Instant::now();
+        let ret = f(self);
+        let elapsed = start.elapsed();
+        self.span_stack.pop();
+
+        match self.spans.iter_mut().find(|(name, _)| *name == path) {
+            Some((_, total)) => *total += elapsed,
+            None => self.spans.push((path, elapsed)),
+        }
+        ret
     }
 
-    pub fn import_key(&mut self, pub_key: &str, priv_key: &str) -> bool {
-        self.client().import_key(self.id, pub_key.into(), priv_key.into()).unwrap()
+    /// Returns the total time spent in each span of `with_span`, in the order the spans were first closed
+    pub fn span_report(&self) -> Vec<(String, Duration)> {
+        self.spans.clone()
     }
 
-    pub fn get_info(&mut self) -> Result<Value> {
-        let ret = self.client().get_info(self.id);
-        parse_ret(&ret)
+    /// Frees the chain, called on drop. A failure is only reported while the thread is panicking,
+    /// since a second panic in `drop` would abort the process.
+    pub fn free(&mut self) {
+        if self.freed {
+            return;
+        }
+        run_chain_hooks(self, false);
+        self.freed = true;
+        get_apply_map_mutex().remove(&self.id);
+        get_boxed_apply_map_mutex().retain(|(id, _), _| *id != self.id);
+        release_chain_slot();
+        let ret = try_get_chain_tester_client().and_then(|mut client| client.free_chain(self.id));
+        if let Err(err) = ret {
+            if thread::panicking() {
+                println!("failed to free chain {}: {:?}", self.id, err);
+            } else {
+                panic!("failed to free chain {}: {:?}", self.id, err);
+            }
+        }
     }
 
-    pub fn create_key(&mut self) -> Result<Value> {
-        let ret = self.client().create_key("K1".into());
-        parse_ret(&ret)
+    pub fn produce_block(&mut self) -> Result<()> {
+        self.produce_block_ex(0)
     }
 
-    pub fn create_key_ex(&mut self, key_type: &str) -> Result<Value> {
-        let ret = self.client().create_key(key_type.into());
+    pub fn produce_block_ex(&mut self, next_block_skip_seconds: i64) -> Result<()> {
+        self.client().produce_block(self.id, next_block_skip_seconds).map_err(|err| {
+            ChainTesterError{json: None, error_string: Some(format!("{:?}", err))}
+        })?;
+        self.record_produced_block();
+        Ok(())
+    }
+
+    // the debugger server has no get_block, so remember who produced each block and its id
+    // in order to answer `get_block_producer` and `get_block_id` later on.
+    // The block is produced at this point, so failing to record it only makes it unknown later.
+    fn record_produced_block(&mut self) {
+        let info = match self.get_info() {
+            Ok(info) => info,
+            Err(_) => return,
+        };
+        if let (Some(block_num), Some(producer), Some(id)) = (info["head_block_num"].as_u64(), info["head_block_producer"].as_str(), info["head_block_id"].as_str()) {
+            self.produced_blocks.insert(block_num, ProducedBlock{producer: producer.into(), id: id.into()});
+        }
+        while self.produced_blocks.len() > MAX_RECORDED_BLOCKS {
+            let oldest = *self.produced_blocks.keys().next().unwrap();
+            self.produced_blocks.remove(&oldest);
+        }
+    }
+
+    fn get_produced_block(&mut self, block_num: u64) -> Result<(String, String)> {
+        let info = self.get_info()?;
+        if info["head_block_num"].as_u64() == Some(block_num) {
+            if let (Some(producer), Some(id)) = (info["head_block_producer"].as_str(), info["head_block_id"].as_str()) {
+                return Ok((producer.into(), id.into()));
+            }
+        }
+        match self.produced_blocks.get(&block_num) {
+            Some(block) => Ok((block.producer.clone(), block.id.clone())),
+            None => Err(ChainTesterError{
+                json: None, error_string: Some(format!("block {} is unknown, only the head block and the last {} blocks produced by this tester are", block_num, MAX_RECORDED_BLOCKS)),
+            }),
+        }
+    }
+
+    /// Returns the id of block `block_num` in hex.
+    ///
+    /// Only the head block and the last `MAX_RECORDED_BLOCKS` blocks produced by this tester can be resolved.
+    pub fn get_block_id(&mut self, block_num: u64) -> Result<String> {
+        Ok(self.get_produced_block(block_num)?.1)
+    }
+
+    /// Returns the account which produced block `block_num`.
+    ///
+    /// Only the head block and the last `MAX_RECORDED_BLOCKS` blocks produced by this tester can be resolved.
+    pub fn get_block_producer(&mut self, block_num: u64) -> Result<String> {
+        Ok(self.get_produced_block(block_num)?.0)
+    }
+
+    /// Produces blocks until `predicate` returns true, see `produce_until_ex`
+    pub fn produce_until<F>(&mut self, predicate: F) -> Result<u32>
+    where F: FnMut(&mut ChainTester) -> bool,
+    {
+        self.produce_until_ex(DEFAULT_PRODUCE_UNTIL_MAX_BLOCKS, predicate)
+    }
+
+    /// Produces blocks until `predicate`, which may query tables, returns true,
+    /// and returns the number of blocks produced. The predicate is checked before each block,
+    /// an error is returned if it still fails after `max_blocks` blocks.
+    pub fn produce_until_ex<F>(&mut self, max_blocks: u32, mut predicate: F) -> Result<u32>
+    where F: FnMut(&mut ChainTester) -> bool,
+    {
+        for blocks in 0..=max_blocks {
+            if predicate(self) {
+                return Ok(blocks);
+            }
+            if blocks < max_blocks {
+                self.produce_block()?;
+            }
+        }
+        Err(ChainTesterError{
+            json: None, error_string: Some(format!("condition not met after producing {} blocks", max_blocks)),
+        })
+    }
+
+    /// Returns the head block time in milliseconds since the unix epoch
+    pub fn get_head_block_time_ms(&mut self) -> Result<i64> {
+        let info = self.get_info()?;
+        info["head_block_time"].as_str().and_then(parse_block_time_ms).ok_or_else(|| {
+            ChainTesterError{json: Some(info.clone()), error_string: Some("invalid head_block_time in chain info".into())}
+        })
+    }
+
+    /// Returns the block interval of the chain, measured from the `head_block_time` of two consecutive blocks.
+    /// A block is produced to measure it on the first call.
+    pub fn get_block_interval_ms(&mut self) -> Result<i64> {
+        if let Some(block_interval_ms) = self.block_interval_ms {
+            return Ok(block_interval_ms);
+        }
+
+        let start = self.get_head_block_time_ms()?;
+        self.produce_block()?;
+        let block_interval_ms = self.get_head_block_time_ms()? - start;
+        if block_interval_ms <= 0 {
+            return Err(ChainTesterError{
+                json: None, error_string: Some(format!("invalid block interval {}ms", block_interval_ms)),
+            });
+        }
+        self.block_interval_ms = Some(block_interval_ms);
+        Ok(block_interval_ms)
+    }
+
+    /// Produces enough blocks to advance the head block time by at least `ms` milliseconds.
+    pub fn advance_time(&mut self, ms: i64) -> Result<()> {
+        let time_ms = self.get_head_block_time_ms()? + ms;
+        self.advance_to(time_ms)
+    }
+
+    /// Produces enough blocks for the head block time to reach `time_ms`, in milliseconds since the unix epoch,
+    /// nothing is produced if it has been reached already.
+    pub fn advance_to(&mut self, time_ms: i64) -> Result<()> {
+        let block_interval_ms = self.get_block_interval_ms()?;
+        let remaining = time_ms - self.get_head_block_time_ms()?;
+        if remaining <= 0 {
+            return Ok(());
+        }
+        for _ in 0..(remaining + block_interval_ms - 1) / block_interval_ms {
+            self.produce_block()?;
+        }
+        Ok(())
+    }
+
+    pub fn enable_debugging(&mut self, enable: bool) -> thrift::Result<()> {
+        self.client().enable_debugging(enable)
+    }
+
+    pub fn set_native_contract(&mut self, contract: &str, dylib: &str) -> thrift::Result<bool> {
+        self.client().set_native_contract(self.id, contract.into(), dylib.into())
+    }
+
+    pub fn set_native_apply(&mut self, contract: &str, apply: Option<FnApply>) -> thrift::Result<()> {
+        let tester_apply_map = &mut get_apply_map_mutex();
+        let apply_map = tester_apply_map.get_mut(&self.id).unwrap();
+        if let Some(_apply) = apply {
+            apply_map.insert(contract.into(), _apply);
+            self.enable_debug_contract(contract, true)?;
+        } else {
+            let _contract: String = contract.into();
+            apply_map.remove(&_contract);
+            self.enable_debug_contract(contract, false)?;
+        }
+        Ok(())
+    }
+
+    /// Same as `set_native_apply` for a handler capturing state,
+    /// a handler set by `set_native_apply` for the same contract takes precedence.
+    pub fn set_native_apply_boxed(&mut self, contract: &str, apply: Option<BoxedApply>) -> Result<()> {
+        let key = (self.id, String::from(contract));
+        let enable = apply.is_some();
+        match apply {
+            Some(apply) => get_boxed_apply_map_mutex().insert(key, apply),
+            None => get_boxed_apply_map_mutex().remove(&key),
+        };
+        self.enable_debug_contract(contract, enable).map_err(|err| {
+            ChainTesterError{json: None, error_string: Some(format!("{:?}", err))}
+        })
+    }
+
+    pub fn enable_debug_contract(&mut self, contract: &str, enable: bool) -> thrift::Result<()> {
+        self.client().enable_debug_contract(self.id, contract.into(), enable)
+    }
+
+    pub fn is_debug_contract_enabled(&mut self, contract: &str) -> thrift::Result<bool> {
+        self.client().is_debug_contract_enabled(self.id, contract.into())
+    }
+
+    pub fn import_key(&mut self, pub_key: &str, priv_key: &str) -> bool {
+        self.client().import_key(self.id, pub_key.into(), priv_key.into()).unwrap()
+    }
+
+    pub fn get_info(&mut self) -> Result<Value> {
+        let ret = self.client().get_info(self.id);
+        parse_ret(&ret)
+    }
+
+    /// Returns the version of the node, e.g. `v3.1.0`
+    pub fn server_version_string(&mut self) -> Result<String> {
+        let info = self.get_info()?;
+        info["server_version_string"].as_str().map(String::from).ok_or_else(|| {
+            ChainTesterError{json: Some(info.clone()), error_string: Some("no server_version_string in chain info".into())}
+        })
+    }
+
+    /// Returns the producer of the head block, which is the active producer of the test chain
+    pub fn get_head_block_producer(&mut self) -> Result<String> {
+        let info = self.get_info()?;
+        info["head_block_producer"].as_str().map(String::from).ok_or_else(|| {
+            ChainTesterError{json: Some(info.clone()), error_string: Some("no head_block_producer in chain info".into())}
+        })
+    }
+
+    /// Pushes an action authorized by `active` permission of the head block producer,
+    /// whose key is known by the debugger.
+    pub fn push_action_as_producer(&mut self, account: &str, action: &str, arguments: ActionArguments) -> Result<Value> {
+        let producer = self.get_head_block_producer()?;
+        let permissions = format!(r#"{{"{}": "active"}}"#, producer);
+        self.push_action(account, action, arguments, &permissions)
+    }
+
+    pub fn create_key(&mut self) -> Result<Value> {
+        let ret = self.client().create_key("K1".into());
+        parse_ret(&ret)
+    }
+
+    pub fn create_key_ex(&mut self, key_type: &str) -> Result<Value> {
+        let ret = self.client().create_key(key_type.into());
         parse_ret(&ret)
     }
 
@@ -505,6 +1393,37 @@ impl ChainTester {
         parse_ret(&ret)
     }
 
+    pub fn get_permission(&mut self, account: &str, permission: &str) -> Result<Authority> {
+        let ret = self.get_account(account)?;
+        let permissions = ret["permissions"].as_array().ok_or_else(|| {
+            ChainTesterError{json: None, error_string: Some(format!("no permissions in account {}", account))}
+        })?;
+
+        for perm in permissions {
+            if perm["perm_name"] != permission {
+                continue;
+            }
+            return Authority::from_json(&perm["required_auth"]).ok_or_else(|| {
+                ChainTesterError{json: None, error_string: Some(format!("invalid authority of {}@{}", account, permission))}
+            });
+        }
+        Err(ChainTesterError{json: None, error_string: Some(format!("permission {}@{} not found", account, permission))})
+    }
+
+    pub fn get_ram_usage(&mut self, account: &str) -> Result<i64> {
+        let ret = self.get_account(account)?;
+        ret["ram_usage"].as_i64().ok_or_else(|| {
+            ChainTesterError{json: None, error_string: Some(format!("no ram_usage in account {}", account))}
+        })
+    }
+
+    pub fn assert_ram_usage_below(&mut self, account: &str, max_bytes: i64) {
+        let ram_usage = self.get_ram_usage(account).unwrap();
+        if ram_usage >= max_bytes {
+            panic!("ram usage of {} exceeds threshold, expect below {}, got {}", account, max_bytes, ram_usage);
+        }
+    }
+
     pub fn create_account(&mut self, creator: &str, account: &str, owner_key: &str, active_key: &str, ram_bytes: i64, stake_net: i64, stake_cpu: i64) -> Result<Value> {
         let ret = self.client().create_account(self.id, creator.into(), account.into(), owner_key.into(), active_key.into(), ram_bytes, stake_net, stake_cpu);
         parse_ret(&ret)
@@ -524,6 +1443,7 @@ impl ChainTester {
                 if tx.get("except").is_some() {
                     Err(ChainTesterError{json: Some(tx), error_string: None})
                 } else {
+                    self.record_transaction(&tx);
                     Ok(tx)
                 }
             }
@@ -535,9 +1455,209 @@ impl ChainTester {
         }
     }
 
+    /// Pushes an action whose transaction may use at most `max_cpu_us` of cpu, so that a cpu heavy action
+    /// fails with a `tx_cpu_usage_exceeded` or `deadline_exception`.
+    /// The debugger server fills in the transaction header, so the limit is the `max_transaction_cpu_usage`
+    /// of the chain, set with `eosio::setparams` for this action only. A block is produced after each change of the parameters.
+    ///
+    /// Returns an error if the limit can't be set, in which case the action is not pushed.
+    /// Otherwise the result of the action and the result of restoring the parameters are both returned.
+    pub fn push_action_with_deadline(&mut self, account: &str, action: &str, arguments: ActionArguments, permissions: &str, max_cpu_us: u32) -> Result<DeadlineReturn> {
+        let ret = self.get_table_rows(true, "eosio", "eosio", "global", "", "", 1)?;
+        let global = table_row_data(&ret["rows"][0]);
+        let mut params = serde_json::Map::new();
+        for field in BLOCKCHAIN_PARAMETERS {
+            match global.get(field) {
+                Some(value) => params.insert(field.into(), value.clone()),
+                None => return Err(ChainTesterError{json: Some(ret.clone()), error_string: Some(format!("no {} in the global state of eosio", field))}),
+            };
+        }
+
+        let mut limited = params.clone();
+        limited.insert("max_transaction_cpu_usage".into(), max_cpu_us.into());
+        self.set_blockchain_parameters(limited)?;
+        let result = self.push_action(account, action, arguments, permissions);
+        let restore = self.set_blockchain_parameters(params);
+        Ok(DeadlineReturn { result, restore })
+    }
+
+    fn set_blockchain_parameters(&mut self, params: serde_json::Map<String, Value>) -> Result<()> {
+        let args = serde_json::json!({"params": params});
+        self.push_action("eosio", "setparams", args.to_string().into(), r#"{"eosio": "active"}"#)?;
+        self.produce_block()
+    }
+
+    /// Pushes the insert `action` twice with the same `arguments`, which insert a row keyed by `key`,
+    /// and panics unless the first push succeeds and the second one is rejected
+    /// by an assertion of the contract or by the uniqueness check of the table.
+    pub fn assert_duplicate_rejected(&mut self, account: &str, action: &str, arguments: &str, permissions: &str, key: &str) {
+        if let Err(err) = self.push_action(account, action, arguments.into(), permissions) {
+            panic!("first insert of key {} failed: {}", key, err);
+        }
+        // the same transaction would be rejected as a duplicate in the same block
+        self.produce_block().unwrap();
+
+        let err = match self.push_action(account, action, arguments.into(), permissions) {
+            Ok(_) => panic!("duplicate key {} accepted by {}::{}", key, account, action),
+            Err(err) => err,
+        };
+
+        let except = err.json.as_ref().map(|json| &json["except"]);
+        let name = except.and_then(|except| except["name"].as_str()).unwrap_or_default();
+        let is_assert = name == "eosio_assert_message_exception" || name == "eosio_assert_code_exception";
+        let is_uniqueness = except.map_or(false, |except| except.to_string().contains("uniqueness constraint"));
+        if !is_assert && !is_uniqueness {
+            panic!("duplicate key {} rejected by an unexpected error: {}", key, err);
+        }
+    }
+
+    /// Pushes `account::action` authorized by `other@active` and panics unless it fails with a `missing_auth_exception`,
+    /// then pushes it authorized by `required@active` and panics if it fails.
+    /// `other` must be an existing account whose authorization does not satisfy the check of the contract.
+    pub fn assert_requires_auth(&mut self, account: &str, action: &str, arguments: &str, required: &str, other: &str) {
+        assert_ne!(required, other, "the other authorizer must differ from the required one");
+        let permissions = serde_json::json!({other: "active"});
+        match self.push_action(account, action, arguments.into(), &permissions.to_string()) {
+            Ok(_) => panic!("{}::{} accepted the authorization of {} instead of {}", account, action, other, required),
+            Err(err) if err.error_name().as_deref() == Some("missing_auth_exception") => {}
+            Err(err) => panic!("{}::{} authorized by {} failed with an unexpected error: {}", account, action, other, err),
+        }
+
+        let permissions = serde_json::json!({required: "active"});
+        if let Err(err) = self.push_action(account, action, arguments.into(), &permissions.to_string()) {
+            panic!("{}::{} authorized by {} failed: {}", account, action, required, err);
+        }
+    }
+
+    /// Pushes an action and returns its transaction trace together with all the notifications it triggered.
+    pub fn push_action_with_notifications(&mut self, account: &str, action: &str, arguments: ActionArguments, permissions: &str) -> Result<(Value, Vec<NotificationTrace>)> {
+        let tx = self.push_action(account, action, arguments, permissions)?;
+        let notifications = NotificationTrace::parse(&tx);
+        Ok((tx, notifications))
+    }
+
+    /// Builds an action to be included in a proposed transaction, `arguments` are packed with the abi of `account`.
+    pub fn new_msig_action(&mut self, account: &str, action: &str, arguments: &str, authorization: &[(&str, &str)]) -> Result<Value> {
+        let data = self.client().pack_action_args(self.id, account.into(), action.into(), arguments.into()).map_err(|err| {
+            ChainTesterError{json: None, error_string: Some(format!("{:?}", err))}
+        })?;
+        Ok(serde_json::json!({
+            "account": account,
+            "name": action,
+            "authorization": permission_levels(authorization),
+            "data": hex::encode(data),
+        }))
+    }
+
+    /// Builds a transaction to be proposed with `msig_propose`.
+    /// The reference block is left empty since `eosio.msig` only checks the expiration, e.g. `"2100-01-01T00:00:00"`.
+    pub fn new_msig_transaction(expiration: &str, actions: Vec<Value>) -> Value {
+        serde_json::json!({
+            "expiration": expiration,
+            "ref_block_num": 0,
+            "ref_block_prefix": 0,
+            "max_net_usage_words": 0,
+            "max_cpu_usage_ms": 0,
+            "delay_sec": 0,
+            "context_free_actions": [],
+            "actions": actions,
+            "transaction_extensions": [],
+        })
+    }
+
+    /// Proposes `trx` to `eosio.msig` as `proposer`, which has to approve it with the `(actor, permission)` levels in `requested`.
+    pub fn msig_propose(&mut self, proposer: &str, proposal_name: &str, requested: &[(&str, &str)], trx: Value) -> Result<Value> {
+        let args = serde_json::json!({
+            "proposer": proposer,
+            "proposal_name": proposal_name,
+            "requested": permission_levels(requested),
+            "trx": trx,
+        });
+        let permissions = serde_json::json!({proposer: "active"});
+        self.push_action("eosio.msig", "propose", args.to_string().into(), &permissions.to_string())
+    }
+
+    /// Approves a proposal with the `(actor, permission)` level `level`.
+    pub fn msig_approve(&mut self, proposer: &str, proposal_name: &str, level: (&str, &str)) -> Result<Value> {
+        let args = serde_json::json!({
+            "proposer": proposer,
+            "proposal_name": proposal_name,
+            "level": {"actor": level.0, "permission": level.1},
+        });
+        let permissions = serde_json::json!({level.0: level.1});
+        self.push_action("eosio.msig", "approve", args.to_string().into(), &permissions.to_string())
+    }
+
+    /// Executes an approved proposal, authorized by the `active` permission of `executer`.
+    pub fn msig_exec(&mut self, proposer: &str, proposal_name: &str, executer: &str) -> Result<Value> {
+        let args = serde_json::json!({
+            "proposer": proposer,
+            "proposal_name": proposal_name,
+            "executer": executer,
+        });
+        let permissions = serde_json::json!({executer: "active"});
+        self.push_action("eosio.msig", "exec", args.to_string().into(), &permissions.to_string())
+    }
+
+    fn record_transaction(&mut self, tx: &Value) {
+        if let Some(id) = tx["id"].as_str() {
+            self.transactions.insert(id.into(), tx.clone());
+            self.transaction_ids.push(id.into());
+        }
+    }
+
+    /// Returns the trace of a transaction pushed by this tester, including `block_num` and `block_time`.
+    ///
+    /// The debugger server does not expose the history api, so only transactions pushed through
+    /// this `ChainTester` can be retrieved.
+    pub fn get_transaction(&mut self, id: &str) -> Result<Value> {
+        match self.transactions.get(&id.to_lowercase()) {
+            Some(tx) => Ok(tx.clone()),
+            None => Err(ChainTesterError{
+                json: None, error_string: Some(format!("transaction {} not found: history is not available, only transactions pushed by this tester can be retrieved", id)),
+            })
+        }
+    }
+
+    /// Returns the traces of the transactions pushed by this tester, in push order.
+    pub fn pushed_transactions(&self) -> Vec<&Value> {
+        self.transaction_ids.iter().map(|id| &self.transactions[id]).collect()
+    }
+
+    pub fn transfer(&mut self, token_account: &str, from: &str, to: &str, quantity: &str, memo: &str) -> Result<Value> {
+        if memo.len() > MAX_MEMO_SIZE {
+            return Err(ChainTesterError{
+                json: None, error_string: Some(format!("memo has more than {} bytes", MAX_MEMO_SIZE)),
+            });
+        }
+
+        let args = serde_json::json!({
+            "from": from,
+            "to": to,
+            "quantity": quantity,
+            "memo": memo,
+        });
+        let permissions = serde_json::json!({from: "active"});
+        self.push_action(token_account, "transfer", args.to_string().into(), &permissions.to_string())
+    }
+
+    /// Deploys the wasm file and, unless `abi_file` is empty, the abi file to `account`
     pub fn deploy_contract(&mut self, account: &str, wasm_file: &str, abi_file: &str) -> Result<Value> {
-        // abi_file.is_empty()
-        let wasm = fs::read(wasm_file).unwrap();        
+        let read = |path: &str| fs::read(path).map_err(|err| ChainTesterError{
+            json: None, error_string: Some(format!("can not read {}: {}", path, err)),
+        });
+        let wasm = read(wasm_file)?;
+        let abi = if abi_file.is_empty() { Vec::new() } else { read(abi_file)? };
+        self.deploy_contract_from_bytes(account, &wasm, &abi)
+    }
+
+    /// Deploys `wasm` and, unless `abi` is empty, the json `abi` to `account`,
+    /// for contracts built in process or embedded with `include_bytes!`
+    pub fn deploy_contract_from_bytes(&mut self, account: &str, wasm: &[u8], abi: &[u8]) -> Result<Value> {
+        self.deployed_abis.remove(account);
+        self.code_hashes.remove(account);
+        let mut deployed_abi: Option<Value> = None;
+        let code_hash = hex::encode(Sha256::digest(wasm));
         let hex_wasm = hex::encode(wasm);
 
         let set_code_args = format!(
@@ -562,7 +1682,9 @@ impl ChainTester {
             account,
         );
 
-        let raw_set_code_args = self.client().pack_action_args(self.id, "eosio".into(), "setcode".into(), set_code_args).unwrap();
+        let raw_set_code_args = self.client().pack_action_args(self.id, "eosio".into(), "setcode".into(), set_code_args).map_err(|err| {
+            ChainTesterError{json: None, error_string: Some(format!("{:?}", err))}
+        })?;
         let mut actions: Vec<Box<Action>> = Vec::new();
         let setcode = Action{
             account: Some("eosio".into()),
@@ -572,10 +1694,16 @@ impl ChainTester {
         };
         actions.push(Box::new(setcode));
 
-        if !abi_file.is_empty() {
-            // let abi = fs::read(Path::new(abi_file)).unwrap();
-            let abi = fs::read_to_string(abi_file).unwrap();
-            let raw_abi = self.client().pack_abi(abi).unwrap();
+        if !abi.is_empty() {
+            let abi = String::from_utf8(abi.to_vec()).map_err(|err| ChainTesterError{
+                json: None, error_string: Some(format!("abi is not valid utf8: {}", err)),
+            })?;
+            deployed_abi = Some(serde_json::from_str(&abi).map_err(|err| ChainTesterError{
+                json: None, error_string: Some(format!("abi is not valid json: {}", err)),
+            })?);
+            let raw_abi = self.client().pack_abi(abi).map_err(|err| {
+                ChainTesterError{json: None, error_string: Some(format!("{:?}", err))}
+            })?;
             let hex_raw_abi = hex::encode(raw_abi);
             let set_abi_args = format!(
                 r#"
@@ -588,7 +1716,9 @@ impl ChainTester {
                 hex_raw_abi
             );
 
-            let raw_setabi = self.client().pack_action_args(self.id, "eosio".into(), "setabi".into(), set_abi_args).unwrap();
+            let raw_setabi = self.client().pack_action_args(self.id, "eosio".into(), "setabi".into(), set_abi_args).map_err(|err| {
+                ChainTesterError{json: None, error_string: Some(format!("{:?}", err))}
+            })?;
             let setabi = Action{
                 account: Some("eosio".into()),
                 action: Some("setabi".into()),
@@ -599,12 +1729,98 @@ impl ChainTester {
             actions.push(Box::new(setabi));    
         }
 
-        self.push_actions(actions)
+        let ret = self.push_actions(actions)?;
+        self.code_hashes.insert(account.into(), code_hash);
+        if let Some(abi) = deployed_abi {
+            self.deployed_abis.insert(account.into(), abi);
+        }
+        Ok(ret)
+    }
+
+    /// Returns the ABI last deployed to `account` by `deploy_contract` or `deploy_contract_from_bytes`
+    /// of this tester, an error is returned for any other account, e.g. the system contracts.
+    ///
+    /// This is not a lookup of the chain state: the debugger server has no call returning the ABI
+    /// or the code of an account.
+    pub fn deployed_abi(&mut self, account: &str) -> Result<Value> {
+        match self.deployed_abis.get(account) {
+            Some(abi) => Ok(abi.clone()),
+            None => Err(ChainTesterError{
+                json: None, error_string: Some(format!("abi of {} not found: only the abis deployed by this tester are known", account)),
+            })
+        }
+    }
+
+    /// Returns the hex encoded sha256 hash of the code last deployed to `account` by this tester, see `deployed_abi`.
+    pub fn deployed_code_hash(&mut self, account: &str) -> Result<String> {
+        match self.code_hashes.get(account) {
+            Some(code_hash) => Ok(code_hash.clone()),
+            None => Err(ChainTesterError{
+                json: None, error_string: Some(format!("code of {} not found: only the code deployed by this tester is known", account)),
+            })
+        }
+    }
+
+    /// Panics with the differences if the ABI deployed to `code` differs from the json ABI in `golden_path`.
+    /// Entries of the ABI sections are compared by name regardless of their order,
+    /// the order of struct fields is significant.
+    pub fn assert_abi_matches(&mut self, code: &str, golden_path: &str) {
+        let abi = match self.deployed_abi(code) {
+            Ok(abi) => abi,
+            Err(err) => panic!("{}", err),
+        };
+        let golden: Value = match fs::read_to_string(golden_path) {
+            Ok(golden) => serde_json::from_str(&golden).unwrap_or_else(|err| panic!("invalid golden abi {}: {}", golden_path, err)),
+            Err(err) => panic!("can not read golden abi {}: {}", golden_path, err),
+        };
+
+        let diff = diff_abi(&golden, &abi);
+        if !diff.is_empty() {
+            panic!("abi of {} differs from {}:\n{}", code, golden_path, diff.join("\n"));
+        }
+    }
+
+    /// Packs the arguments of `account::action` given as `(field name, value)` pairs in any order,
+    /// using the abi of `account` to find the field order.
+    pub fn pack_action_fields(&mut self, account: &str, action: &str, fields: &[(&str, Value)]) -> Result<Vec<u8>> {
+        let abi = self.deployed_abi(account)?;
+        let args = order_action_fields(&abi, action, fields)?;
+        self.client().pack_action_args(self.id, account.into(), action.into(), args.to_string()).map_err(|err| {
+            ChainTesterError{json: None, error_string: Some(format!("{:?}", err))}
+        })
+    }
+
+    /// Returns the code hash, ABI version, action names and table names of a contract deployed by `deploy_contract`.
+    pub fn get_contract_info(&mut self, account: &str) -> Result<ContractInfo> {
+        let code_hash = self.deployed_code_hash(account)?;
+
+        let abi = self.deployed_abi(account)?;
+        let names = |key: &str| -> Vec<String> {
+            abi[key].as_array().map(|items| {
+                items.iter().filter_map(|item| item["name"].as_str().map(String::from)).collect()
+            }).unwrap_or_default()
+        };
+
+        Ok(ContractInfo {
+            code_hash,
+            abi_version: abi["version"].as_str().unwrap_or_default().into(),
+            actions: names("actions"),
+            tables: names("tables"),
+        })
     }
 
     pub fn push_actions(&mut self, actions: Vec<Box<Action>>) -> Result<Value> {
         let ret = self.client().push_actions(self.id, actions);
-        parse_ret2(&ret)
+        let tx = parse_ret2(&ret)?;
+        self.record_transaction(&tx);
+        Ok(tx)
+    }
+
+    /// Pushes each action in its own transaction, so a failing action does not revert the others,
+    /// and returns one result per action in order. No block is produced between the transactions,
+    /// interleave `produce_block` calls to get one block per action.
+    pub fn push_actions_separately(&mut self, actions: Vec<Box<Action>>) -> Vec<Result<Value>> {
+        actions.into_iter().map(|action| self.push_actions(vec![action])).collect()
     }
 
     pub fn get_table_rows(&mut self, json: bool, code: &str, scope: &str, table: &str, lower_bound: &str, upper_bound: &str, limit: i64) -> Result<Value> {
@@ -626,6 +1842,202 @@ impl ChainTester {
 
     }
 
+    /// Returns the primary keys of the rows whose `i64` secondary index at `index_position`
+    /// is in `[lower, upper]`, in secondary index order.
+    /// The primary key is read from the field `primary_key_field` of the json rows, since the abi doesn't
+    /// tell which field it is, an error is returned for a row without it.
+    pub fn get_table_by_secondary(&mut self, code: &str, scope: &str, table: &str, index_position: &str, lower: &str, upper: &str, primary_key_field: &str) -> Result<Vec<u64>> {
+        let mut primary_keys = Vec::new();
+        let mut lower_bound = lower.to_string();
+        loop {
+            let params = GetTableRowsPrams {
+                json: true,
+                code: code,
+                scope: scope,
+                table: table,
+                lower_bound: &lower_bound,
+                upper_bound: upper,
+                limit: 100,
+                key_type: "i64",
+                index_position: index_position,
+                show_payer: false,
+                ..Default::default()
+            };
+            let ret = self.get_table_rows_ex(&params)?;
+
+            for row in ret["rows"].as_array().into_iter().flatten() {
+                // u64 values which don't fit in a double are strings, as are names
+                let primary_key = match &row[primary_key_field] {
+                    Value::Number(key) => key.as_u64(),
+                    Value::String(key) if key.bytes().all(|c| c.is_ascii_digit()) => key.parse().ok(),
+                    Value::String(key) => try_s2n(key),
+                    _ => None,
+                };
+                match primary_key {
+                    Some(primary_key) => primary_keys.push(primary_key),
+                    None => {
+                        return Err(ChainTesterError{
+                            json: Some(row.clone()), error_string: Some(format!("primary key {} not found in row of {}", primary_key_field, table)),
+                        });
+                    }
+                }
+            }
+
+            match ret["next_key"].as_str() {
+                Some(next_key) if ret["more"].as_bool() == Some(true) && !next_key.is_empty() => {
+                    lower_bound = next_key.into();
+                }
+                _ => {
+                    return Ok(primary_keys);
+                }
+            }
+        }
+    }
+
+    /// Returns the bytes stored in the row of `primary_key`, without decoding them with the abi,
+    /// which helps to find out mismatches between the abi and the stored data.
+    pub fn get_table_row_raw(&mut self, code: &str, scope: &str, table: &str, primary_key: u64) -> Result<Vec<u8>> {
+        let key = primary_key.to_string();
+        let params = GetTableRowsPrams {
+            json: false,
+            code: code,
+            scope: scope,
+            table: table,
+            lower_bound: &key,
+            upper_bound: &key,
+            limit: 1,
+            key_type: "i64",
+            ..Default::default()
+        };
+        let ret = self.get_table_rows_ex(&params)?;
+        match ret["rows"].as_array().and_then(|rows| rows.first()) {
+            Some(row) => row.as_str().and_then(|data| hex::decode(data).ok()).ok_or_else(|| {
+                ChainTesterError{json: Some(row.clone()), error_string: Some("invalid table row".into())}
+            }),
+            None => Err(ChainTesterError{
+                json: None, error_string: Some(format!("row {} not found in {}:{}:{}", primary_key, code, scope, table)),
+            }),
+        }
+    }
+
+    /// Returns the names of the tables in the abi of `code`
+    pub fn table_names(&mut self, code: &str) -> Result<Vec<String>> {
+        Ok(self.get_contract_info(code)?.tables)
+    }
+
+    /// Counts the rows of a table, reading them in pages of 100 rows
+    pub fn count_table_rows(&mut self, code: &str, scope: &str, table: &str) -> Result<u64> {
+        let mut count = 0;
+        let mut lower_bound = String::new();
+        loop {
+            let params = GetTableRowsPrams::builder()
+                .json(false)
+                .code(code)
+                .scope(scope)
+                .table(table)
+                .lower_bound(&lower_bound)
+                .limit(100)
+                .build();
+            let ret = self.get_table_rows_ex(&params)?;
+            count += ret["rows"].as_array().map_or(0, |rows| rows.len()) as u64;
+
+            match ret["next_key"].as_str() {
+                Some(next_key) if ret["more"].as_bool() == Some(true) && !next_key.is_empty() => {
+                    lower_bound = next_key.into();
+                }
+                _ => {
+                    return Ok(count);
+                }
+            }
+        }
+    }
+
+    /// Returns the row count of each table of `code` in the default scope, which `new_table` uses
+    pub fn table_summary(&mut self, code: &str) -> Result<Vec<(String, u64)>> {
+        self.table_summary_ex(code, "")
+    }
+
+    /// Returns the row count of each table of `code` in `scope`
+    pub fn table_summary_ex(&mut self, code: &str, scope: &str) -> Result<Vec<(String, u64)>> {
+        let mut summary = Vec::new();
+        for table in self.table_names(code)? {
+            let count = self.count_table_rows(code, scope, &table)?;
+            summary.push((table, count));
+        }
+        Ok(summary)
+    }
+
+    /// Returns all the rows of a table as json, in primary key order
+    pub fn get_all_table_rows(&mut self, code: &str, scope: &str, table: &str) -> Result<Vec<Value>> {
+        let mut rows = Vec::new();
+        let mut lower_bound = String::new();
+        loop {
+            let params = GetTableRowsPrams {
+                code: code,
+                scope: scope,
+                table: table,
+                lower_bound: &lower_bound,
+                limit: 100,
+                ..Default::default()
+            };
+            let ret = self.get_table_rows_ex(&params)?;
+            rows.extend(ret["rows"].as_array().into_iter().flatten().cloned());
+
+            match ret["next_key"].as_str() {
+                Some(next_key) if ret["more"].as_bool() == Some(true) && !next_key.is_empty() => {
+                    lower_bound = next_key.into();
+                }
+                _ => {
+                    return Ok(rows);
+                }
+            }
+        }
+    }
+
+    /// Panics with the differing rows if the table is not equal to `expected`,
+    /// an array of the json rows in primary key order, which is the order of the rows returned by the chain.
+    pub fn assert_table_equals(&mut self, code: &str, scope: &str, table: &str, expected: Value) {
+        let rows = self.get_all_table_rows(code, scope, table).unwrap();
+        let expected = match expected {
+            Value::Array(expected) => expected,
+            _ => panic!("expected rows of {} should be an array", table),
+        };
+
+        let diff = diff_rows(&expected, &rows);
+        if !diff.is_empty() {
+            panic!("table {} of {} in scope {} differs:\n{}", table, code, scope, diff.join("\n"));
+        }
+    }
+
+    /// Pushes `account::action` twice, producing a block after each push, and panics if the second push
+    /// changed any of the `tables` of `account`, given as `(scope, table)` pairs.
+    pub fn assert_idempotent(&mut self, account: &str, action: &str, arguments: &str, permissions: &str, tables: &[(&str, &str)]) {
+        let mut snapshots = Vec::new();
+        for i in 0..2 {
+            if let Err(err) = self.push_action(account, action, arguments.into(), permissions) {
+                panic!("push {} of {}::{} failed: {}", i + 1, account, action, err);
+            }
+            // the same transaction would be rejected as a duplicate in the same block
+            self.produce_block().unwrap();
+
+            let mut snapshot = Vec::new();
+            for (scope, table) in tables {
+                snapshot.push(self.get_all_table_rows(account, scope, table).unwrap());
+            }
+            snapshots.push(snapshot);
+        }
+
+        let mut diff = Vec::new();
+        for ((scope, table), (first, second)) in tables.iter().zip(snapshots[0].iter().zip(snapshots[1].iter())) {
+            for d in diff_rows(first, second) {
+                diff.push(format!("table {} in scope {}: {}", table, scope, d));
+            }
+        }
+        if !diff.is_empty() {
+            panic!("{}::{} is not idempotent:\n{}", account, action, diff.join("\n"));
+        }
+    }
+
     pub fn get_table_rows_ex(&mut self, params: &GetTableRowsPrams) -> Result<Value> {
         let ret = self.client().get_table_rows(self.id,
             params.json,
@@ -645,18 +2057,41 @@ impl ChainTester {
     }
 
     pub fn get_balance(&mut self, account: &str) -> u64 {
-        return self.get_balance_ex(account, "eosio.token", "EOS");
+        return self.get_balance_raw(account, "eosio.token", "EOS").unwrap();
     }
 
-    pub fn get_balance_ex(&mut self, account: &str, token_account: &str, symbol: &str) -> u64 {
-        let ret = self.get_table_rows(false, token_account, account, "accounts", symbol, "", 1).unwrap();
+    /// Returns the balance of `account` in the smallest unit of `symbol`, e.g. `10000` for `1.0000 EOS`,
+    /// 0 if `account` has no balance of `symbol`.
+    /// `rust_chain::table_rows::get_balance` returns the balance as an `Asset` with its precision.
+    pub fn get_balance_raw(&mut self, account: &str, token_account: &str, symbol: &str) -> Result<u64> {
+        let ret = self.get_table_rows(false, token_account, account, "accounts", symbol, "", 1)?;
+        let row = match ret["rows"].as_array().and_then(|rows| rows.first()) {
+            Some(row) => row,
+            None => return Ok(0),
+        };
+        let error = |msg: String| ChainTesterError{json: Some(row.clone()), error_string: Some(msg)};
+        let data = match table_row_data(row).as_str().and_then(|data| hex::decode(data).ok()) {
+            Some(data) if data.len() >= 16 => data,
+            _ => return Err(error("invalid balance row".into())),
+        };
+        // the row of the next symbol is returned when `account` has no balance of `symbol`,
+        // the symbol code follows the amount and the precision
+        let code: Vec<u8> = data[9..16].iter().copied().take_while(|c| *c != 0).collect();
+        if code != symbol.as_bytes() {
+            return Ok(0);
+        }
+        let amount = i64::from_le_bytes(data[..8].try_into().unwrap());
+        u64::try_from(amount).map_err(|_| error(format!("negative balance {}", amount)))
+    }
+
+    pub fn get_supply(&mut self, token_account: &str, symbol: &str) -> u64 {
+        let ret = self.get_table_rows(false, token_account, symbol, "stat", symbol, "", 1).unwrap();
         let rows = ret["rows"].as_array().unwrap();
         if rows.len() == 0 {
             return 0;
         }
-        let balance = rows[0]["data"].as_str().unwrap();
-        let _balance = hex::decode(balance).unwrap();
-        let amount: [u8;8] = match _balance[0..8].try_into() {
+        let supply = hex::decode(rows[0]["data"].as_str().unwrap()).unwrap();
+        let amount: [u8;8] = match supply[0..8].try_into() {
             Ok(v) => v,
             Err(_) => {
                 panic!("invalid value");
@@ -664,6 +2099,16 @@ impl ChainTester {
         };
         return u64::from_le_bytes(amount);
     }
+
+    /// Checks that the balances of `accounts` add up to the supply of `symbol` issued by `token_account`.
+    /// `accounts` must list every holder of the token, including the issuer.
+    pub fn is_supply_conserved(&mut self, token_account: &str, symbol: &str, accounts: &[&str]) -> bool {
+        let mut total: u128 = 0;
+        for account in accounts {
+            total += self.get_balance_raw(account, token_account, symbol).unwrap() as u128;
+        }
+        return total == self.get_supply(token_account, symbol) as u128;
+    }
 }
 
 // pub enum ActionArguments {
@@ -671,6 +2116,115 @@ impl ChainTester {
 //     Binary(Vec<u8>),
 // }
 
+/// Describes the rows that differ between `expected` and `actual`, empty if they are equal
+fn diff_rows(expected: &[Value], actual: &[Value]) -> Vec<String> {
+    let mut diff = Vec::new();
+    for i in 0..std::cmp::max(expected.len(), actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(e), Some(a)) if e != a => diff.push(format!("row {}: expected {}, got {}", i, e, a)),
+            (Some(e), None) => diff.push(format!("row {}: missing {}", i, e)),
+            (None, Some(a)) => diff.push(format!("row {}: unexpected {}", i, a)),
+            _ => {}
+        }
+    }
+    diff
+}
+
+/// ABI sections and the field identifying their entries
+const ABI_SECTIONS: [(&str, &str); 8] = [
+    ("types", "new_type_name"),
+    ("structs", "name"),
+    ("actions", "name"),
+    ("tables", "name"),
+    ("variants", "name"),
+    ("ricardian_clauses", "id"),
+    ("error_messages", "error_code"),
+    ("action_results", "name"),
+];
+
+/// Describes the differences between the `expected` and `actual` json ABIs, empty if they are equal.
+/// A missing section is the same as an empty one.
+fn diff_abi(expected: &Value, actual: &Value) -> Vec<String> {
+    let mut diff = Vec::new();
+    if expected["version"] != actual["version"] {
+        diff.push(format!("version: expected {}, got {}", expected["version"], actual["version"]));
+    }
+
+    for (section, key) in ABI_SECTIONS {
+        let entries = |abi: &Value| -> std::collections::BTreeMap<String, Value> {
+            abi[section].as_array().into_iter().flatten().map(|entry| {
+                (entry[key].to_string().trim_matches('"').to_string(), entry.clone())
+            }).collect()
+        };
+        let (expected, actual) = (entries(expected), entries(actual));
+        for (name, e) in &expected {
+            match actual.get(name) {
+                Some(a) if a != e => diff.push(format!("{} {}: expected {}, got {}", section, name, e, a)),
+                None => diff.push(format!("{} {}: missing {}", section, name, e)),
+                _ => {}
+            }
+        }
+        for (name, a) in &actual {
+            if !expected.contains_key(name) {
+                diff.push(format!("{} {}: unexpected {}", section, name, a));
+            }
+        }
+    }
+    diff
+}
+
+fn abi_error<T>(msg: String) -> Result<T> {
+    Err(ChainTesterError{json: None, error_string: Some(msg)})
+}
+
+fn abi_struct_fields<'a>(abi: &'a Value, name: &str, out: &mut Vec<&'a Value>) -> Result<()> {
+    let structs = abi["structs"].as_array().map(|v| v.as_slice()).unwrap_or_default();
+    let s = match structs.iter().find(|s| s["name"] == name) {
+        Some(s) => s,
+        None => return abi_error(format!("struct {} not found in abi", name)),
+    };
+
+    if let Some(base) = s["base"].as_str() {
+        if !base.is_empty() {
+            abi_struct_fields(abi, base, out)?;
+        }
+    }
+    out.extend(s["fields"].as_array().into_iter().flatten());
+    Ok(())
+}
+
+/// Returns the arguments of `action` as a json object with the fields in abi order
+fn order_action_fields(abi: &Value, action: &str, fields: &[(&str, Value)]) -> Result<Value> {
+    let actions = abi["actions"].as_array().map(|v| v.as_slice()).unwrap_or_default();
+    let ty = match actions.iter().find(|a| a["name"] == action) {
+        Some(a) => a["type"].as_str().unwrap_or_default(),
+        None => return abi_error(format!("action {} not found in abi", action)),
+    };
+
+    let mut abi_fields = Vec::new();
+    abi_struct_fields(abi, ty, &mut abi_fields)?;
+
+    for (name, _) in fields {
+        if !abi_fields.iter().any(|field| field["name"] == *name) {
+            return abi_error(format!("unknown field {} of action {}", name, action));
+        }
+    }
+
+    let mut args = serde_json::Map::new();
+    for field in abi_fields {
+        let name = field["name"].as_str().unwrap_or_default();
+        match fields.iter().find(|(n, _)| *n == name) {
+            Some((_, value)) => {
+                args.insert(name.into(), value.clone());
+            }
+            // binary extensions can only be followed by binary extensions
+            None if field["type"].as_str().unwrap_or_default().ends_with('$') => break,
+            None => return abi_error(format!("missing field {} of action {}", name, action)),
+        }
+    }
+    Ok(Value::Object(args))
+}
+
 impl From<String> for ActionArguments {
     fn from(value: String) -> Self {
         ActionArguments::JsonArgs(value)
@@ -699,44 +2253,113 @@ pub fn new_vm_api_client(
     host: &str,
     port: u16,
 ) -> thrift::Result<ApplySyncClient<ClientInputProtocol, ClientOutputProtocol>> {
-    let mut c = TTcpChannel::new();
+    let config = crate::get_debugger_config().vm_api_client;
+    new_vm_api_client_ex(host, port, &config)
+}
+
+/// Connects to the VM API server, retrying as set in `config` while the server is starting,
+/// and returns the error of the last attempt once the retries are exhausted.
+pub fn new_vm_api_client_ex(
+    host: &str,
+    port: u16,
+    config: &VmApiClientConfig,
+) -> thrift::Result<ApplySyncClient<ClientInputProtocol, ClientOutputProtocol>> {
+    let rpc_timeout = crate::get_debugger_config().rpc_timeout;
 
     // open the underlying TCP stream
     println!("connecting to VM API server on {}:{}", host, port);
     //wait for vm api server to start
     thread::sleep(Duration::from_micros(10));
     let remote_address = format!("{}:{}", host, port);
-    for i in 0..=10 {
-        match c.open(&remote_address) {
-            Ok(()) => {
-                break;
+    let mut i = 0;
+    let c = loop {
+        match open_tcp_channel(&remote_address, config.connect_timeout, rpc_timeout) {
+            Ok(c) => {
+                break c;
             }
             Err(err) => {
-                if i == 10 {
-                    panic!("{}", err)
+                if i >= config.max_retries {
+                    return Err(err);
                 } else {
                     println!("+++++++vm_api_client error: {}", err);
-                    thread::sleep(Duration::from_micros(200));    
+                    thread::sleep(config.retry_delay);
                 }
             }
         }
-    }
+        i += 1;
+    };
 
     println!("VM API server connected!");
 
+    let (i_prot, o_prot) = new_client_protocols(c)?;
+    // we're done!
+    Ok(ApplySyncClient::new(i_prot, o_prot))
+}
+
+/// Parses a block time such as `2023-01-01T00:00:00.500` to milliseconds since the unix epoch
+fn parse_block_time_ms(time: &str) -> Option<i64> {
+    let (date, time) = time.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|v| v.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (time, ms) = match time.split_once('.') {
+        Some((time, ms)) => (time, format!("{:0<3}", ms).get(..3)?.parse::<i64>().ok()?),
+        None => (time, 0),
+    };
+    let mut time = time.splitn(3, ':').map(|v| v.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // days since 1970-01-01 of the proleptic gregorian calendar
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+    Some(((days * 24 + hour) * 60 + minute) * 60_000 + second * 1000 + ms)
+}
+
+fn permission_levels(levels: &[(&str, &str)]) -> Value {
+    levels.iter().map(|(actor, permission)| serde_json::json!({"actor": actor, "permission": permission})).collect()
+}
+
+/// Opens a TCP channel whose reads and writes fail after `timeout` instead of blocking forever,
+/// so that a request stuck in a write buffer surfaces as an error rather than a deadlock.
+fn open_tcp_channel(address: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>) -> thrift::Result<TTcpChannel> {
+    let stream = match connect_timeout {
+        Some(connect_timeout) => {
+            let addr = address.to_socket_addrs()?.next().ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("can not resolve {}", address))
+            })?;
+            TcpStream::connect_timeout(&addr, connect_timeout)?
+        }
+        None => TcpStream::connect(address)?,
+    };
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)?;
+    Ok(TTcpChannel::with_stream(stream))
+}
+
+fn new_client_protocols(c: TTcpChannel) -> thrift::Result<(ClientInputProtocol, ClientOutputProtocol)> {
     // clone the TCP channel into two halves, one which
     // we'll use for reading, the other for writing
     let (i_chan, o_chan) = c.split()?;
 
-    // wrap the raw sockets (slow) with a buffered transport of some kind
+    // wrap the raw sockets (slow) with a buffered transport of some kind.
+    // every generated client method flushes the write transport after writing its request.
     let i_tran = TBufferedReadTransport::new(i_chan);
     let o_tran = TBufferedWriteTransport::new(o_chan);
 
-    // now create the protocol implementations
+    // now create the protocol implementations.
+    // reads are not strict so that replies without a version header are also accepted,
+    // the debugger server always sends one. a stream that lost sync is then read as an unversioned
+    // header, which is most likely rejected by the service name and sequence number checks.
+    // reads always loop until the requested bytes have arrived, so fragmented replies are fine.
     let i_prot = TBinaryInputProtocol::new(i_tran, false);
     let o_prot = TBinaryOutputProtocol::new(o_tran, true);
-    // we're done!
-    Ok(ApplySyncClient::new(i_prot, o_prot))
+    Ok((i_prot, o_prot))
 }
 
 ///
@@ -774,3 +2397,628 @@ pub fn n2s(value: u64) -> String {
     };
     return r;
 }
+
+/// Converts a name string to its `u64` value, the inverse of `n2s`.
+/// Panics on invalid names, like `Name::from_str` of rust-chain.
+pub fn s2n(name: &str) -> u64 {
+	match try_s2n(name) {
+		Some(value) => value,
+		None => panic!("bad name string: {}", name),
+	}
+}
+
+/// Same as `s2n`, but returns `None` on invalid names
+fn try_s2n(name: &str) -> Option<u64> {
+	let charmap = ".12345abcdefghijklmnopqrstuvwxyz".as_bytes();
+	let s = name.as_bytes();
+	if s.len() > 13 {
+		return None;
+	}
+
+	let mut value: u64 = 0;
+	for i in 0..13 {
+		let c = match s.get(i) {
+			Some(c) => charmap.iter().position(|x| x == c)? as u64,
+			None => 0,
+		};
+		if i < 12 {
+			value |= c << (64 - 5 * (i + 1));
+		} else {
+			// the last character has only 4 bits
+			if c > 0x0f {
+				return None;
+			}
+			value |= c;
+		}
+	}
+	Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_s2n() {
+        assert_eq!(s2n(""), 0);
+        assert_eq!(s2n("eosio"), 0x5530ea0000000000);
+        assert_eq!(s2n("eosio.token"), 0x5530ea033482a600);
+        for name in ["eosio", "eosio.token", "eosio.msig", "alice", "helloworld11", "zzzzzzzzzzzzj", "a.b.c"] {
+            assert_eq!(n2s(s2n(name)), name);
+        }
+        for value in [0u64, 1, 0x5530ea0000000000, u64::MAX] {
+            assert_eq!(s2n(&n2s(value)), value);
+        }
+        for name in ["Alice", "alice-1", "helloworld1234", "zzzzzzzzzzzzz"] {
+            assert_eq!(try_s2n(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "bad name string")]
+    fn test_s2n_invalid() {
+        s2n("Alice");
+    }
+
+    #[test]
+    fn test_error_code() {
+        let err = ChainTesterError{json: Some(serde_json::json!({
+            "except": {
+                "code": 3050003,
+                "name": "eosio_assert_message_exception",
+                "stack": [{"data": {"s": "overdrawn balance"}}],
+            }
+        })), error_string: None};
+        assert_eq!(err.error_code(), Some(3050003));
+        assert_eq!(err.error_name().unwrap(), "eosio_assert_message_exception");
+        err.assert_code(3050003);
+        err.check_err("overdrawn balance");
+        assert!(err.expect_err("overdrawn balance").is_ok());
+        assert_eq!(err.expect_err("no balance").unwrap_err(), "invalid error, expect no balance, got overdrawn balance");
+
+        let err = ChainTesterError{json: None, error_string: Some("connection refused".into())};
+        assert_eq!(err.error_code(), None);
+        assert_eq!(err.error_name(), None);
+        assert!(err.expect_err("overdrawn balance").unwrap_err().contains("connection refused"));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid error code, expect 3050003, got 3080004")]
+    fn test_assert_code_mismatch() {
+        let err = ChainTesterError{json: Some(serde_json::json!({
+            "except": {"code": 3080004, "name": "tx_cpu_usage_exceeded"}
+        })), error_string: None};
+        err.assert_code(3050003);
+    }
+
+    #[test]
+    fn test_diff_abi() {
+        let golden = serde_json::json!({
+            "version": "eosio::abi/1.1",
+            "structs": [
+                {"name": "inc", "base": "", "fields": []},
+                {"name": "transfer", "base": "", "fields": [{"name": "from", "type": "name"}, {"name": "to", "type": "name"}]},
+            ],
+            "actions": [{"name": "inc", "type": "inc", "ricardian_contract": ""}],
+        });
+
+        // the order of the entries does not matter, a missing section is an empty one
+        let mut abi = golden.clone();
+        abi["structs"].as_array_mut().unwrap().reverse();
+        abi["tables"] = serde_json::json!([]);
+        assert!(diff_abi(&golden, &abi).is_empty());
+
+        // the order of the fields does
+        abi["structs"][0]["fields"].as_array_mut().unwrap().reverse();
+        abi["actions"] = serde_json::json!([{"name": "dec", "type": "inc", "ricardian_contract": ""}]);
+        let diff = diff_abi(&golden, &abi);
+        assert_eq!(diff.len(), 3);
+        assert!(diff[0].starts_with("structs transfer: expected"));
+        assert!(diff[1].starts_with("actions inc: missing"));
+        assert!(diff[2].starts_with("actions dec: unexpected"));
+    }
+
+    // `create_key` is used below since, unlike most calls, it doesn't wait on the apply request server
+    #[test]
+    fn test_rpc_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let c = open_tcp_channel(&address, None, Some(Duration::from_millis(200))).unwrap();
+        // accept the connection but never reply, like a server still waiting for the rest of a request
+        let (_stream, _) = listener.accept().unwrap();
+
+        let (i_prot, o_prot) = new_client_protocols(c).unwrap();
+        let mut client = IPCChainTesterSyncClient::new(i_prot, o_prot);
+        let start = Instant::now();
+        assert!(client.create_key("K1".into()).is_err());
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    // reply of `create_key` returning `value`, as written by a strict binary protocol
+    fn create_key_reply(seq: i32, value: &str) -> Vec<u8> {
+        let mut reply = Vec::new();
+        reply.extend((0x80010000u32 | 2).to_be_bytes());
+        reply.extend(("create_key".len() as i32).to_be_bytes());
+        reply.extend("create_key".as_bytes());
+        reply.extend(seq.to_be_bytes());
+        // field 0 (success) of type string
+        reply.push(11);
+        reply.extend(0i16.to_be_bytes());
+        reply.extend((value.len() as i32).to_be_bytes());
+        reply.extend(value.as_bytes());
+        // field stop
+        reply.push(0);
+        reply
+    }
+
+    #[test]
+    fn test_fragmented_reply() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let c = open_tcp_channel(&address, None, Some(Duration::from_secs(10))).unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+
+        let value = r#"{"public": "EOS6MRyAjQq8ud7hVNYcfnVPJqcVpscN5So8BhtHuGYqET5GDW5CV"}"#;
+        let reply = create_key_reply(1, value);
+        let server = thread::spawn(move || {
+            // split the reply inside the header, the field header and the string
+            for fragment in [&reply[..3], &reply[3..20], &reply[20..24], &reply[24..40], &reply[40..]] {
+                stream.write_all(fragment).unwrap();
+                stream.flush().unwrap();
+                thread::sleep(Duration::from_millis(20));
+            }
+            stream
+        });
+
+        let (i_prot, o_prot) = new_client_protocols(c).unwrap();
+        let mut client = IPCChainTesterSyncClient::new(i_prot, o_prot);
+        assert_eq!(client.create_key("K1".into()).unwrap(), value);
+        server.join().unwrap();
+    }
+
+    // serializes the tests changing the debugger config
+    static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+    fn free_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+    }
+
+    fn set_local_config(debugger_port: u16, vm_api_port: u16, apply_request_port: u16) {
+        let mut config = crate::get_debugger_config();
+        config.debugger_server_address = "127.0.0.1".into();
+        config.debugger_server_port = debugger_port;
+        config.vm_api_server_address = "127.0.0.1".into();
+        config.vm_api_server_port = vm_api_port;
+        config.apply_request_server_address = "127.0.0.1".into();
+        config.apply_request_server_port = apply_request_port;
+        config.rpc_timeout = Some(Duration::from_secs(10));
+        config.vm_api_client.max_retries = 2;
+        config.vm_api_client.retry_delay = Duration::from_millis(10);
+    }
+
+    // accepts the debugger and vm api connections, then connects back to the apply request server
+    fn connect_back(debugger: &TcpListener, vm_api: &TcpListener, apply_request_port: u16) -> thread::JoinHandle<(TcpStream, TcpStream, TcpStream)> {
+        let (debugger, vm_api) = (debugger.try_clone().unwrap(), vm_api.try_clone().unwrap());
+        thread::spawn(move || {
+            let (debugger_stream, _) = debugger.accept().unwrap();
+            let (vm_api_stream, _) = vm_api.accept().unwrap();
+            // connect back to the apply request server once it is listening
+            let start = Instant::now();
+            let apply_request_stream = loop {
+                match TcpStream::connect(("127.0.0.1", apply_request_port)) {
+                    Ok(s) => break s,
+                    Err(err) => {
+                        assert!(start.elapsed() < Duration::from_secs(10), "{}", err);
+                        thread::sleep(Duration::from_millis(10));
+                    }
+                }
+            };
+            (debugger_stream, vm_api_stream, apply_request_stream)
+        })
+    }
+
+    #[test]
+    fn test_init_cleanup_on_failure() {
+        let _config_lock = CONFIG_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let debugger = TcpListener::bind("127.0.0.1:0").unwrap();
+        let vm_api_port = free_port();
+        let apply_request_port = free_port();
+        set_local_config(debugger.local_addr().unwrap().port(), vm_api_port, apply_request_port);
+
+        // nothing listens on the vm api port, so init fails after connecting to the debugger
+        let mut client = ChainTesterClient::new();
+        assert!(client.init().is_err());
+        assert!(client.client.is_none());
+        assert_ne!(client.status(), ClientStatus::Running);
+        assert!(VM_API_CLIENT.lock().unwrap().vm_api_client.is_none());
+
+        // the half-open connection has been closed
+        let (mut stream, _) = debugger.accept().unwrap();
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).unwrap();
+
+        let vm_api = TcpListener::bind(("127.0.0.1", vm_api_port)).unwrap();
+        let server = connect_back(&debugger, &vm_api, apply_request_port);
+
+        client.init().unwrap();
+        assert_eq!(client.status(), ClientStatus::Running);
+        let _streams = server.join().unwrap();
+        client.close();
+    }
+
+    #[test]
+    fn test_init_close_init() {
+        let _config_lock = CONFIG_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        let debugger = TcpListener::bind("127.0.0.1:0").unwrap();
+        let vm_api = TcpListener::bind("127.0.0.1:0").unwrap();
+        let apply_request_port = free_port();
+        set_local_config(debugger.local_addr().unwrap().port(), vm_api.local_addr().unwrap().port(), apply_request_port);
+
+        let mut client = ChainTesterClient::new();
+        assert_eq!(client.status(), ClientStatus::Uninitialized);
+        for _ in 0..2 {
+            let server = connect_back(&debugger, &vm_api, apply_request_port);
+            client.init().unwrap();
+            assert_eq!(client.status(), ClientStatus::Running);
+            let (mut debugger_stream, mut vm_api_stream, mut apply_request_stream) = server.join().unwrap();
+
+            // no-op while running, nothing else connects
+            client.init().unwrap();
+            debugger.set_nonblocking(true).unwrap();
+            assert_eq!(debugger.accept().unwrap_err().kind(), std::io::ErrorKind::WouldBlock);
+            debugger.set_nonblocking(false).unwrap();
+
+            client.close();
+            assert_eq!(client.status(), ClientStatus::Closed);
+            assert!(VM_API_CLIENT.lock().unwrap().vm_api_client.is_none());
+            // every connection of this run has been closed
+            let mut buf = Vec::new();
+            debugger_stream.read_to_end(&mut buf).unwrap();
+            vm_api_stream.read_to_end(&mut buf).unwrap();
+            apply_request_stream.read_to_end(&mut buf).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_block_time_ms() {
+        assert_eq!(parse_block_time_ms("1970-01-01T00:00:00.000"), Some(0));
+        assert_eq!(parse_block_time_ms("1970-01-01T00:00:01"), Some(1000));
+        assert_eq!(parse_block_time_ms("2000-03-01T00:00:00.500"), Some(951868800500));
+        assert_eq!(parse_block_time_ms("2023-01-01T12:30:00.5"), Some(1672576200500));
+        for time in ["", "2023-01-01", "2023-13-01T00:00:00", "2023-01-01T24:00:00", "2023-01-01T00:00:00.x"] {
+            assert_eq!(parse_block_time_ms(time), None, "{}", time);
+        }
+    }
+
+    #[test]
+    fn test_get_table_rows_params_builder() {
+        let params = GetTableRowsPrams::builder()
+            .code("eosio.token")
+            .scope("alice")
+            .table("accounts")
+            .index_position("2")
+            .key_type("i64")
+            .lower_bound("1")
+            .limit(100)
+            .reverse(true)
+            .show_payer(true)
+            .build();
+        assert!(params.json);
+        assert_eq!((params.code, params.scope, params.table), ("eosio.token", "alice", "accounts"));
+        assert_eq!((params.index_position, params.key_type), ("2", "i64"));
+        assert_eq!((params.lower_bound, params.upper_bound), ("1", ""));
+        assert_eq!(params.limit, 100);
+        assert!(params.reverse && params.show_payer);
+        assert_eq!(params.encode_type, "");
+
+        let params = GetTableRowsPrams::builder().json(false).build();
+        assert!(!params.json);
+        assert_eq!(params.limit, 10);
+    }
+
+    #[test]
+    fn test_name_bounds() {
+        let bounds = NameBounds::new(s2n("alice"), s2n("bob"));
+        let params = GetTableRowsPrams::builder().name_bounds(&bounds).build();
+        assert_eq!((params.key_type, params.encode_type), ("i64", "dec"));
+        assert_eq!(params.lower_bound, s2n("alice").to_string());
+        assert_eq!(params.upper_bound, s2n("bob").to_string());
+    }
+
+    #[test]
+    fn test_decode_table_rows() {
+        #[derive(serde::Deserialize)]
+        struct Account {
+            balance: String,
+        }
+
+        let ret = serde_json::json!({
+            "rows": [
+                {"data": {"balance": "1.0000 EOS"}, "payer": "alice"},
+                {"data": {"balance": "2.5000 EOS"}, "payer": "bob"},
+            ],
+            "more": true,
+            "next_key": "5459781",
+        });
+        let rows = decode_table_rows(&ret, decode_json_row::<Account>).unwrap();
+        assert_eq!(rows.rows.len(), 2);
+        assert_eq!(rows.rows[1].balance, "2.5000 EOS");
+        assert!(rows.more);
+        assert_eq!(rows.next_key, "5459781");
+
+        let ret = serde_json::json!({"rows": [{"balance": "3.0000 EOS"}], "more": false, "next_key": ""});
+        let rows = decode_table_rows(&ret, decode_json_row::<Account>).unwrap();
+        assert_eq!(rows.rows[0].balance, "3.0000 EOS");
+        assert!(!rows.more);
+
+        let ret = serde_json::json!({"rows": [{"amount": 1}]});
+        assert!(decode_table_rows(&ret, decode_json_row::<Account>).is_err());
+
+    }
+
+    #[test]
+    fn test_try_new_without_server() {
+        let _config_lock = CONFIG_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        crate::get_debugger_config().debugger_server_port = free_port();
+
+        let err = ChainTester::try_new().err().unwrap();
+        assert!(err.error_string.unwrap().contains("is the chaintester server running?"));
+        assert_ne!(get_chain_tester_status(), ClientStatus::Running);
+        // the client mutex is still usable after the failure
+        assert!(ChainTester::try_new_ex(false).is_err());
+    }
+
+    // `ChainTester::new` takes a slot before creating its chain and `free` gives it back
+    #[test]
+    fn test_max_chain_count() {
+        let _config_lock = CONFIG_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        set_max_chain_count(2);
+        acquire_chain_slot().unwrap();
+        acquire_chain_slot().unwrap();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let third = thread::spawn(move || {
+            acquire_chain_slot().unwrap();
+            tx.send(()).unwrap();
+        });
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+        release_chain_slot();
+        rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        third.join().unwrap();
+        assert_eq!(CHAIN_LIMIT.0.lock().unwrap().live, 2);
+
+        // a slot that is never given back, e.g. by a leaked tester, fails after the timeout
+        set_max_chain_count_ex(2, Duration::from_millis(100));
+        let err = acquire_chain_slot().unwrap_err();
+        assert!(err.error_string.unwrap().contains("no chain freed within"));
+        assert_eq!(CHAIN_LIMIT.0.lock().unwrap().live, 2);
+
+        release_chain_slot();
+        release_chain_slot();
+        set_max_chain_count(0);
+    }
+
+    #[test]
+    fn test_vm_api_client_retries() {
+        let config = VmApiClientConfig {
+            max_retries: 3,
+            retry_delay: Duration::from_millis(50),
+            connect_timeout: Some(Duration::from_secs(1)),
+        };
+
+        // nothing listens on the port: fails after 3 retries instead of panicking
+        let port = free_port();
+        let start = Instant::now();
+        assert!(new_vm_api_client_ex("127.0.0.1", port, &config).is_err());
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        // the server starts while the client is retrying
+        let server = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(60));
+            let listener = TcpListener::bind(("127.0.0.1", port)).unwrap();
+            listener.accept().unwrap()
+        });
+        assert!(new_vm_api_client_ex("127.0.0.1", port, &config).is_ok());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_apply_watchdog() {
+        static REPORTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+        let mut client = VMAPIClient::new();
+        client.set_in_apply(true);
+        assert!(client.stuck_apply_duration(Duration::from_secs(60)).is_none());
+        thread::sleep(Duration::from_millis(20));
+        assert!(client.stuck_apply_duration(Duration::from_millis(10)).is_some());
+        client.set_in_apply(false);
+        assert!(client.stuck_apply_duration(Duration::from_millis(10)).is_none());
+
+        // leave the flag set, as a panicking handler would
+        VM_API_CLIENT.lock().unwrap().set_in_apply(true);
+        let watchdog = start_apply_watchdog(Duration::from_millis(20), |_| {
+            REPORTS.fetch_add(1, Ordering::SeqCst);
+        });
+        let start = Instant::now();
+        while REPORTS.load(Ordering::SeqCst) == 0 && start.elapsed() < Duration::from_secs(5) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        thread::sleep(Duration::from_millis(100));
+        drop(watchdog);
+        VM_API_CLIENT.lock().unwrap().set_in_apply(false);
+        // reported once for the leaked context
+        assert_eq!(REPORTS.load(Ordering::SeqCst), 1);
+    }
+
+    fn new_transaction_return(cpu_usage_us: u64, net_usage_words: u64) -> TransactionReturn {
+        TransactionReturn::new(serde_json::json!({
+            "receipt": {
+                "status": "executed",
+                "cpu_usage_us": cpu_usage_us,
+                "net_usage_words": net_usage_words,
+            }
+        }))
+    }
+
+    #[test]
+    fn test_ram_deltas() {
+        let ret = TransactionReturn::new(serde_json::json!({
+            "action_traces": [
+                {
+                    "action_ordinal": 1,
+                    "creator_action_ordinal": 0,
+                    "receiver": "hello",
+                    "act": {"account": "hello", "name": "store"},
+                    "account_ram_deltas": [{"account": "hello", "delta": 240}]
+                },
+                {
+                    "action_ordinal": 2,
+                    "creator_action_ordinal": 1,
+                    "receiver": "bob",
+                    "act": {"account": "bob", "name": "onstore"},
+                    "account_ram_deltas": [{"account": "bob", "delta": 120}, {"account": "hello", "delta": -16}]
+                }
+            ]
+        }));
+
+        let deltas = ret.ram_deltas();
+        assert_eq!(deltas.len(), 3);
+        assert_eq!(deltas[1], RamDelta {
+            account: "bob".into(),
+            delta: 120,
+            action_ordinal: 2,
+            receiver: "bob".into(),
+            action_account: "bob".into(),
+            action_name: "onstore".into(),
+        });
+        assert_eq!(deltas[2].account, "hello");
+        assert_eq!(deltas[2].action_name, "onstore");
+        assert_eq!(ret.ram_delta_of("hello"), 224);
+        assert_eq!(ret.ram_delta_of("alice"), 0);
+    }
+
+    #[test]
+    fn test_inline_action_count() {
+        let ret = TransactionReturn::new(serde_json::json!({
+            "action_traces": [
+                {"action_ordinal": 1, "creator_action_ordinal": 0, "receiver": "alice", "act": {"account": "alice", "name": "test"}},
+                // notification, not an inline action
+                {"action_ordinal": 2, "creator_action_ordinal": 1, "receiver": "hello", "act": {"account": "alice", "name": "test"}},
+                {"action_ordinal": 3, "creator_action_ordinal": 2, "receiver": "hello", "act": {"account": "hello", "name": "saygoodbye"}}
+            ]
+        }));
+        assert_eq!(ret.inline_action_count(), 1);
+
+        let ret = TransactionReturn::new(serde_json::json!({
+            "action_traces": [
+                {"action_ordinal": 1, "creator_action_ordinal": 0, "receiver": "alice", "act": {"account": "alice", "name": "test"}},
+                {"action_ordinal": 2, "creator_action_ordinal": 1, "receiver": "hello", "act": {"account": "alice", "name": "test"}}
+            ]
+        }));
+        assert_eq!(ret.inline_action_count(), 0);
+        ret.assert_no_inline_actions();
+    }
+
+    #[test]
+    fn test_parse_notifications() {
+        // hello::transfer notifies alice, whose handler sends alice::log, which notifies bob
+        let tx = serde_json::json!({
+            "action_traces": [
+                {"action_ordinal": 1, "creator_action_ordinal": 0, "receiver": "hello", "act": {"account": "hello", "name": "transfer"}, "console": ""},
+                {"action_ordinal": 2, "creator_action_ordinal": 1, "receiver": "alice", "act": {"account": "hello", "name": "transfer"}, "console": "received"},
+                {"action_ordinal": 3, "creator_action_ordinal": 2, "receiver": "alice", "act": {"account": "alice", "name": "log"}, "console": ""},
+                {"action_ordinal": 4, "creator_action_ordinal": 3, "receiver": "bob", "act": {"account": "alice", "name": "log"}, "console": ""}
+            ]
+        });
+
+        let notifications = NotificationTrace::parse(&tx);
+        assert_eq!(notifications.len(), 2);
+        let n = &notifications[0];
+        assert_eq!((n.receiver.as_str(), n.account.as_str(), n.action.as_str()), ("alice", "hello", "transfer"));
+        assert_eq!((n.action_ordinal, n.console.as_str()), (2, "received"));
+        assert_eq!(n.inline_actions.len(), 1);
+        assert_eq!(n.inline_actions[0]["act"]["name"], "log");
+        assert_eq!(n.trace, tx["action_traces"][1]);
+
+        let n = &notifications[1];
+        assert_eq!((n.receiver.as_str(), n.account.as_str(), n.action.as_str()), ("bob", "alice", "log"));
+        assert!(n.inline_actions.is_empty());
+
+        assert!(NotificationTrace::parse(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_order_action_fields() {
+        let abi = serde_json::json!({
+            "structs": [
+                {"name": "transfer", "base": "", "fields": [
+                    {"name": "from", "type": "name"},
+                    {"name": "to", "type": "name"},
+                    {"name": "quantity", "type": "asset"},
+                    {"name": "memo", "type": "string"},
+                    {"name": "extra", "type": "string$"}
+                ]}
+            ],
+            "actions": [{"name": "transfer", "type": "transfer", "ricardian_contract": ""}]
+        });
+
+        let fields = [
+            ("memo", Value::from("hello")),
+            ("quantity", Value::from("1.0000 EOS")),
+            ("to", Value::from("bob")),
+            ("from", Value::from("alice")),
+        ];
+        let args = order_action_fields(&abi, "transfer", &fields).unwrap();
+        assert_eq!(args.to_string(), r#"{"from":"alice","to":"bob","quantity":"1.0000 EOS","memo":"hello"}"#);
+
+        assert!(order_action_fields(&abi, "transfer", &fields[1..]).is_err());
+        assert!(order_action_fields(&abi, "issue", &fields).is_err());
+        let mut unknown = fields.to_vec();
+        unknown.push(("amount", Value::from(1)));
+        assert!(order_action_fields(&abi, "transfer", &unknown).is_err());
+    }
+
+    #[test]
+    fn test_diff_rows() {
+        let expected = vec![
+            serde_json::json!({"key": 1, "value": 11}),
+            serde_json::json!({"key": 2, "value": 22}),
+        ];
+        assert!(diff_rows(&expected, &expected).is_empty());
+
+        let actual = vec![
+            serde_json::json!({"key": 1, "value": 11}),
+            serde_json::json!({"key": 2, "value": 23}),
+            serde_json::json!({"key": 3, "value": 33}),
+        ];
+        assert_eq!(diff_rows(&expected, &actual), vec![
+            r#"row 1: expected {"key":2,"value":22}, got {"key":2,"value":23}"#.to_string(),
+            r#"row 2: unexpected {"key":3,"value":33}"#.to_string(),
+        ]);
+        assert_eq!(diff_rows(&actual[..1], &[]), vec![r#"row 0: missing {"key":1,"value":11}"#.to_string()]);
+    }
+
+    #[test]
+    fn test_usage_within() {
+        let ret = new_transaction_return(104, 16);
+        ret.assert_cpu_within(100, 5.0);
+        ret.assert_cpu_within(108, 5.0);
+        ret.assert_net_within(16, 0.0);
+        ret.assert_net_within(15, 10.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cpu usage 300 is not within 5% of 100")]
+    fn test_cpu_not_within() {
+        new_transaction_return(300, 16).assert_cpu_within(100, 5.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "net usage 16 is not within 5% of 10")]
+    fn test_net_not_within() {
+        new_transaction_return(100, 16).assert_net_within(10, 5.0);
+    }
+}
@@ -22,21 +22,62 @@ use std::sync::{
 };
 
 pub mod interfaces;
+pub mod authority;
 pub mod client;
 pub use client::{
     new_vm_api_client,
+    new_vm_api_client_ex,
     ChainTester,
     get_vm_api_client,
     close_vm_api_client,
     get_globals,
     get_test_mutex,
     n2s,
+    s2n,
     GetTableRowsPrams,
+    GetTableRowsPramsBuilder,
+    NameBounds,
+    TableRows,
+    TransactionReturn,
+    DeadlineReturn,
+    RamDelta,
+    NotificationTrace,
+    ContractInfo,
+    BoxedApply,
+    ClientStatus,
+    get_chain_tester_status,
+    set_max_chain_count,
+    set_max_chain_count_ex,
+    register_chain_init_hook,
+    register_chain_teardown_hook,
+    start_apply_watchdog,
+    chain_creation_stats,
+    ApplyWatchdog,
 };
 
 pub mod server;
 
 
+/// Connection attempts to the VM API server, which may still be starting when the client connects
+#[derive(Clone, Copy, Debug)]
+pub struct VmApiClientConfig {
+    /// attempts after the first one
+    pub max_retries: u32,
+    pub retry_delay: std::time::Duration,
+    /// timeout of each attempt, `None` uses the timeout of the OS
+    pub connect_timeout: Option<std::time::Duration>,
+}
+
+impl Default for VmApiClientConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 50,
+            retry_delay: std::time::Duration::from_millis(100),
+            connect_timeout: None,
+        }
+    }
+}
+
 pub struct DebuggerConfig {
     pub debugger_server_address: String,
     pub debugger_server_port: u16,
@@ -44,6 +85,10 @@ pub struct DebuggerConfig {
     pub vm_api_server_port: u16,
     pub apply_request_server_address: String,
     pub apply_request_server_port: u16,
+    /// Read/write timeout of the client connections, `None` blocks forever,
+    /// which is needed when pausing in a debugger.
+    pub rpc_timeout: Option<std::time::Duration>,
+    pub vm_api_client: VmApiClientConfig,
 }
 
 impl DebuggerConfig {
@@ -55,6 +100,8 @@ impl DebuggerConfig {
             vm_api_server_port: 9092,
             apply_request_server_address: "127.0.0.1".into(), 
             apply_request_server_port: 9091,
+            rpc_timeout: None,
+            vm_api_client: VmApiClientConfig::default(),
         }
     }
 }
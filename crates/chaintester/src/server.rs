@@ -14,7 +14,7 @@ use thrift::server::{
 };
 use thrift::TransportErrorKind;
 
-use crate::client::get_apply_map_mutex;
+use crate::client::{get_apply_map_mutex, get_boxed_apply_map_mutex};
 use crate::interfaces::{Uint64};
 use crate::interfaces::TApplySyncClient;
 
@@ -306,11 +306,14 @@ impl ApplyRequestSyncHandler for ApplyRequestHandler {
 
         crate::get_vm_api_client().set_in_apply(true);
         let apply_map = &get_apply_map_mutex()[&chain_tester_id];
+        let boxed_apply_map = &get_boxed_apply_map_mutex();
 
         let result = panic::catch_unwind(|| {
             let contract = crate::n2s(_receiver);
             if let Some(apply) = apply_map.get(&contract) {
                 apply(_receiver, _first_receiver, _action);
+            } else if let Some(apply) = boxed_apply_map.get(&(chain_tester_id, contract)) {
+                apply(_receiver, _first_receiver, _action);
             }
         });
 
@@ -369,15 +372,25 @@ pub fn run_apply_request_server()  -> thrift::Result<()> {
 }
 
 pub fn init_apply_request_server() {
+    try_init_apply_request_server().unwrap();
+}
+
+pub(crate) fn try_init_apply_request_server() -> thrift::Result<()> {
     let mut ret = APPLY_REQUEST_SERVER.lock().unwrap();
     if ret.server.cnn.is_none() {
         println!("apply_request server: waiting for debugger connection");
         let host = crate::get_debugger_config().apply_request_server_address.clone();
         let port = crate::get_debugger_config().apply_request_server_port;
         let address = format!("{}:{}", host, port);
-        ret.server.accept(address).unwrap();
+        ret.server.accept(address)?;
         println!("apply_request server: debugger connected");
     }
+    Ok(())
+}
+
+pub fn close_apply_request_server() {
+    let mut ret = APPLY_REQUEST_SERVER.lock().unwrap();
+    ret.server.cnn = None;
 }
 
 pub fn get_apply_request_server() -> MutexGuard<'static, ApplyRequestServer> {
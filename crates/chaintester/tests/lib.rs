@@ -79,7 +79,7 @@ mod tests {
         "#;
 
         tester.push_action("eosio", "updateauth", updateauth_args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -103,7 +103,364 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "inc", "".into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
     }
+    #[test]
+    fn test_chain_init_hook() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static IMPORTED: AtomicUsize = AtomicUsize::new(0);
+
+        chaintester::register_chain_init_hook(Box::new(|tester| {
+            let key = tester.create_key().unwrap();
+            let imported = tester.import_key(key["public"].as_str().unwrap(), key["private"].as_str().unwrap());
+            assert!(imported);
+            IMPORTED.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let before = IMPORTED.load(Ordering::SeqCst);
+        let _tester1 = ChainTester::new();
+        let _tester2 = ChainTester::new();
+        assert_eq!(IMPORTED.load(Ordering::SeqCst), before + 2);
+    }
+
+    #[test]
+    fn test_attach() {
+        let mut tester = ChainTester::new();
+        let key = tester.create_key().unwrap();
+        let pub_key = key["public"].as_str().unwrap();
+        tester.create_account("hello", "helloworld44", pub_key, pub_key, 10*1024*1024, 100000, 100000).unwrap();
+        tester.produce_block().unwrap();
+        let chain_id = tester.get_info().unwrap()["chain_id"].clone();
+        let id = tester.leak();
+
+        let mut tester = ChainTester::attach(id).unwrap();
+        assert_eq!(tester.id(), id);
+        assert_eq!(tester.get_info().unwrap()["chain_id"], chain_id);
+        assert!(tester.get_account("helloworld44").is_ok());
+    }
+
+    #[test]
+    fn test_with_frees_chain_on_panic() {
+        use std::sync::atomic::{AtomicI32, Ordering};
+        static ID: AtomicI32 = AtomicI32::new(-1);
+
+        let ret = std::panic::catch_unwind(|| {
+            ChainTester::with(|tester| {
+                ID.store(tester.id(), Ordering::SeqCst);
+                assert!(tester.get_info().is_ok());
+                panic!("failure inside of the closure");
+            })
+        });
+        assert!(ret.is_err());
+
+        // the chain is gone from the server
+        let id = ID.load(Ordering::SeqCst);
+        assert_ne!(id, -1);
+        assert!(ChainTester::attach(id).is_err());
+
+        let count = ChainTester::with(|tester| tester.get_info().unwrap()["head_block_num"].as_u64().unwrap());
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn test_produce_block_error() {
+        let mut tester = ChainTester::new();
+        tester.produce_block().unwrap();
+
+        // free the chain behind the back of `tester`
+        let other = ChainTester::attach(tester.id()).unwrap();
+        drop(other);
+
+        let err = tester.produce_block().unwrap_err();
+        assert!(err.error_string.is_some() || err.json.is_some());
+        assert!(tester.produce_block_ex(10).is_err());
+        // the chain is already freed
+        tester.leak();
+    }
+
+    #[test]
+    fn test_chain_creation_stats() {
+        chaintester::get_globals().set_debug_mode(true);
+        let (count, _) = chaintester::chain_creation_stats();
+        for _ in 0..3 {
+            ChainTester::new();
+        }
+        // other tests may create chains concurrently
+        assert!(chaintester::chain_creation_stats().0 >= count + 3);
+    }
+
+    #[test]
+    fn test_get_block_producer() {
+        let mut tester = ChainTester::new();
+        tester.produce_block().unwrap();
+        let block_num = tester.get_info().unwrap()["head_block_num"].as_u64().unwrap();
+        let producer = tester.get_head_block_producer().unwrap();
+        assert_eq!(tester.get_block_producer(block_num).unwrap(), producer);
+        assert!(tester.get_block_producer(block_num + 1).is_err());
+
+        // still known once it is no longer the head block
+        tester.produce_block().unwrap();
+        assert_eq!(tester.get_block_producer(block_num).unwrap(), producer);
+    }
+
+    #[test]
+    fn test_push_action_as_producer() {
+        let mut tester = ChainTester::new();
+        let key = tester.create_key().unwrap();
+        let pub_key = key["public"].as_str().unwrap();
+        tester.create_account("hello", "helloworld33", pub_key, pub_key, 10*1024*1024, 100000, 100000).unwrap();
+        tester.produce_block().unwrap();
+
+        // setpriv needs the authority of eosio, the producer of the test chain
+        assert_eq!(tester.get_head_block_producer().unwrap(), "eosio");
+        let args = r#"{"account": "helloworld33", "is_priv": 1}"#;
+        tester.push_action_as_producer("eosio", "setpriv", args.into()).unwrap();
+        tester.produce_block().unwrap();
+        assert_eq!(tester.get_account("helloworld33").unwrap()["privileged"], true);
+
+        // hello is not a producer
+        let args = r#"{"account": "helloworld33", "is_priv": 0}"#;
+        let err = tester.push_action("eosio", "setpriv", args.into(), r#"{"hello": "active"}"#).unwrap_err();
+        // missing_auth_exception
+        err.assert_code(3090004);
+        tester.produce_block().unwrap();
+        assert_eq!(tester.get_account("helloworld33").unwrap()["privileged"], true);
+    }
+
+    #[test]
+    fn test_get_transaction() {
+        let mut tester = ChainTester::new();
+        let key = tester.create_key().unwrap();
+        let pub_key = key["public"].as_str().unwrap();
+        tester.create_account("hello", "helloworld99", pub_key, pub_key, 10*1024*1024, 100000, 100000).unwrap();
+        tester.produce_block().unwrap();
+
+        let args = r#"{"account": "helloworld99", "is_priv": 0}"#;
+        let ret = tester.push_action("eosio", "setpriv", args.into(), r#"{"eosio": "active"}"#).unwrap();
+        let id = ret["id"].as_str().unwrap().to_string();
+        tester.produce_block().unwrap();
+
+        let tx = tester.get_transaction(&id).unwrap();
+        assert_eq!(tx["id"], id.as_str());
+        assert_eq!(tx["block_num"], ret["block_num"]);
+        assert!(tx["block_time"].is_string());
+        // ids are accepted in upper case too
+        assert!(tester.get_transaction(&id.to_uppercase()).is_ok());
+
+        // pushed through another tester
+        let mut other = ChainTester::new();
+        let err = other.get_transaction(&id).unwrap_err();
+        assert!(err.error_string.unwrap().contains("only transactions pushed by this tester"));
+    }
+
+    #[test]
+    fn test_advance_time() {
+        let mut tester = ChainTester::new();
+        let block_interval_ms = tester.get_block_interval_ms().unwrap();
+        assert_eq!(block_interval_ms, 500);
+
+        let start = tester.get_head_block_time_ms().unwrap();
+        tester.advance_to(start + 3000).unwrap();
+        assert_eq!(tester.get_head_block_time_ms().unwrap(), start + 3000);
+        // already reached
+        tester.advance_to(start).unwrap();
+        assert_eq!(tester.get_head_block_time_ms().unwrap(), start + 3000);
+
+        // rounded up to whole blocks
+        tester.advance_time(1200).unwrap();
+        assert_eq!(tester.get_head_block_time_ms().unwrap(), start + 3000 + 1500);
+    }
+
+    #[test]
+    fn test_get_permission() {
+        use chaintester::authority::{KeyWeight, PermissionLevel, PermissionLevelWeight};
+
+        let mut tester = ChainTester::new();
+        let key = tester.create_key().unwrap();
+        let pub_key = key["public"].as_str().unwrap();
+        tester.create_account("hello", "helloworld12", pub_key, pub_key, 10*1024*1024, 100000, 100000).unwrap();
+        tester.produce_block().unwrap();
+
+        let updateauth_args = serde_json::json!({
+            "account": "helloworld12",
+            "permission": "active",
+            "parent": "owner",
+            "auth": {
+                "threshold": 2,
+                "keys": [{"key": "EOS6AjF6hvF7GSuSd4sCgfPKq5uWaXvGM2aQtEUCwmEHygQaqxBSV", "weight": 1}],
+                "accounts": [{"permission": {"actor": "helloworld12", "permission": "eosio.code"}, "weight": 1}],
+                "waits": []
+            }
+        });
+        tester.push_action("eosio", "updateauth", updateauth_args.to_string().into(), r#"{"helloworld12": "owner"}"#).unwrap();
+        tester.produce_block().unwrap();
+
+        let auth = tester.get_permission("helloworld12", "active").unwrap();
+        assert_eq!(auth.threshold, 2);
+        assert_eq!(auth.keys, vec![KeyWeight{key: "EOS6AjF6hvF7GSuSd4sCgfPKq5uWaXvGM2aQtEUCwmEHygQaqxBSV".into(), weight: 1}]);
+        assert_eq!(auth.accounts, vec![PermissionLevelWeight{
+            permission: PermissionLevel{actor: "helloworld12".into(), permission: "eosio.code".into()},
+            weight: 1,
+        }]);
+        assert!(auth.waits.is_empty());
+
+        // the owner permission is unchanged
+        let auth = tester.get_permission("helloworld12", "owner").unwrap();
+        assert_eq!((auth.threshold, auth.keys[0].key.as_str()), (1, pub_key));
+        assert!(tester.get_permission("helloworld12", "missing").is_err());
+    }
+
+    #[test]
+    fn test_msig() {
+        let mut tester = ChainTester::new();
+        let key = tester.create_key().unwrap();
+        let pub_key = key["public"].as_str().unwrap();
+        tester.create_account("hello", "helloworld66", pub_key, pub_key, 10*1024*1024, 100000, 100000).unwrap();
+        tester.produce_block().unwrap();
+        assert_eq!(tester.get_account("helloworld66").unwrap()["privileged"], false);
+
+        let args = r#"{"account": "helloworld66", "is_priv": 1}"#;
+        let action = tester.new_msig_action("eosio", "setpriv", args, &[("eosio", "active")]).unwrap();
+        let trx = ChainTester::new_msig_transaction("2100-01-01T00:00:00", vec![action]);
+        tester.msig_propose("hello", "setpriv", &[("eosio", "active")], trx).unwrap();
+        tester.produce_block().unwrap();
+
+        // not approved yet
+        assert!(tester.msig_exec("hello", "setpriv", "hello").is_err());
+
+        tester.msig_approve("hello", "setpriv", ("eosio", "active")).unwrap();
+        tester.produce_block().unwrap();
+        tester.msig_exec("hello", "setpriv", "hello").unwrap();
+        tester.produce_block().unwrap();
+        assert_eq!(tester.get_account("helloworld66").unwrap()["privileged"], true);
+    }
+
+    #[test]
+    fn test_name_bounds() {
+        // proposals are keyed by their name in the scope of the proposer
+        let mut tester = ChainTester::new();
+        for proposal_name in ["propa", "propb", "propc", "propd"] {
+            let args = r#"{"account": "hello", "is_priv": 1}"#;
+            let action = tester.new_msig_action("eosio", "setpriv", args, &[("eosio", "active")]).unwrap();
+            let trx = ChainTester::new_msig_transaction("2100-01-01T00:00:00", vec![action]);
+            tester.msig_propose("hello", proposal_name, &[("eosio", "active")], trx).unwrap();
+        }
+        tester.produce_block().unwrap();
+
+        let bounds = chaintester::NameBounds::new(chaintester::s2n("propb"), chaintester::s2n("propc"));
+        let params = chaintester::GetTableRowsPrams::builder()
+            .code("eosio.msig")
+            .scope("hello")
+            .table("proposal")
+            .name_bounds(&bounds)
+            .build();
+        let ret = tester.get_table_rows_ex(&params).unwrap();
+        let names: Vec<&str> = ret["rows"].as_array().unwrap().iter().map(|row| row["proposal_name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["propb", "propc"]);
+    }
+
+    #[test]
+    fn test_server_version_string() {
+        let mut tester = ChainTester::new();
+        assert!(!tester.server_version_string().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_deploy_contract_from_bytes() {
+        let mut tester = ChainTester::new();
+        let err = tester.deploy_contract("hello", "./target/missing.wasm", "").unwrap_err();
+        assert!(err.error_string.unwrap().contains("missing.wasm"));
+
+        let wasm = std::fs::read("./target/tests.wasm").unwrap();
+        let err = tester.deploy_contract_from_bytes("hello", &wasm, b"{not json").unwrap_err();
+        assert!(err.error_string.unwrap().contains("abi is not valid json"));
+        assert!(tester.deployed_abi("hello").is_err());
+
+        let abi = super::generate_abi();
+        tester.deploy_contract_from_bytes("hello", &wasm, abi.as_bytes()).unwrap();
+        tester.produce_block().unwrap();
+        assert!(tester.deployed_abi("hello").is_ok());
+    }
+
+    #[test]
+    fn test_deployed_abi() {
+        let mut tester = ChainTester::new();
+        // nothing deployed by this tester yet
+        assert!(tester.deployed_abi("hello").unwrap_err().error_string.unwrap().contains("only the abis deployed by this tester"));
+        assert!(tester.deployed_code_hash("hello").is_err());
+
+        deploy_contract(&mut tester);
+        let abi: serde_json::Value = serde_json::from_str(&std::fs::read_to_string("./target/tests.abi").unwrap()).unwrap();
+        assert_eq!(tester.deployed_abi("hello").unwrap(), abi);
+        let code_hash = tester.deployed_code_hash("hello").unwrap();
+        assert_eq!(hex::decode(&code_hash).unwrap().len(), 32);
+        assert!(tester.deployed_abi("alice").is_err());
+
+        // redeploying the code alone forgets the abi
+        tester.produce_block().unwrap();
+        let wasm = std::fs::read("./target/tests.wasm").unwrap();
+        tester.deploy_contract_from_bytes("hello", &wasm, &[]).unwrap();
+        assert!(tester.deployed_abi("hello").is_err());
+        assert_eq!(tester.deployed_code_hash("hello").unwrap(), code_hash);
+    }
+
+    #[test]
+    fn test_push_actions_separately() {
+        use chaintester::interfaces::Action;
+
+        let mut tester = ChainTester::new();
+        let key = tester.create_key().unwrap();
+        let pub_key = key["public"].as_str().unwrap();
+        tester.create_account("hello", "helloworld77", pub_key, pub_key, 10*1024*1024, 100000, 100000).unwrap();
+        tester.produce_block().unwrap();
+
+        let setpriv = |permissions: &str| Box::new(Action{
+            account: Some("eosio".into()),
+            action: Some("setpriv".into()),
+            permissions: Some(permissions.into()),
+            arguments: Some(r#"{"account": "helloworld77", "is_priv": 1}"#.into()),
+        });
+        // the second one lacks the authority of eosio
+        let actions = vec![setpriv(r#"{"eosio": "active"}"#), setpriv(r#"{"hello": "active"}"#)];
+        let ret = tester.push_actions_separately(actions);
+        assert_eq!(ret.len(), 2);
+        assert!(ret[0].is_ok());
+        assert!(ret[1].is_err());
+
+        tester.produce_block().unwrap();
+        assert_eq!(tester.get_account("helloworld77").unwrap()["privileged"], true);
+    }
+
+    #[test]
+    fn test_with_span() {
+        use std::time::Duration;
+
+        let mut tester = ChainTester::new();
+        let pub_key = tester.with_span("setup", |tester| {
+            let key = tester.create_key().unwrap();
+            let pub_key = key["public"].as_str().unwrap().to_string();
+            tester.create_account("hello", "helloworld88", &pub_key, &pub_key, 10*1024*1024, 100000, 100000).unwrap();
+            tester.produce_block().unwrap();
+            pub_key
+        });
+        assert!(!pub_key.is_empty());
+
+        for _ in 0..2 {
+            tester.with_span("assert", |tester| {
+                std::thread::sleep(Duration::from_millis(20));
+                tester.with_span("account", |tester| {
+                    assert!(tester.get_account("helloworld88").is_ok());
+                });
+            });
+        }
+
+        let report = tester.span_report();
+        let names: Vec<&str> = report.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["setup", "assert/account", "assert"]);
+        assert!(report[0].1 > Duration::ZERO);
+        // both runs are added up, the nested span is part of its parent
+        assert!(report[2].1 >= Duration::from_millis(40));
+        assert!(report[2].1 >= report[1].1);
+    }
 }
@@ -393,7 +393,7 @@ impl Contract {
         match name {
             "bool" | "i8" | "u8" | "i16" | "u16" | "i32" | "u32" | "i64" | "u64" | "f32" | "f64" | "i128" | "u128" |
             "String" |
-            "Varint32" | "VarUint32" | "Float128" | "TimePoint" | "TimePointSec" |
+            "VarInt32" | "VarUint32" | "Float128" | "TimePoint" | "TimePointSec" |
             "BlockTimeStampType" | "Name" | "Checksum160" | "Checksum256" | "Uint256" |
             "Checksum512" | "PublicKey" | "Signature" | "Symbol" | "SymbolCode" | "Asset" |
             "ExtendedAsset" => {
@@ -524,6 +524,15 @@ impl Contract {
                 )
             });
 
+            let try_deserialize = packer.fields.iter().map(|packer_field| {
+                let span = packer_field.span();
+                let ident = &packer_field.ident;
+                let ty = &packer_field.ty;
+                quote_spanned!(span=>
+                    dec.try_unpack::<#ty>(&mut self.#ident)?;
+                )
+            });
+
             let get_size = packer.fields.iter().map(|packer_field| {
                 let span = packer_field.span();
                 let ident = &packer_field.ident;
@@ -551,6 +560,12 @@ impl Contract {
                         #( #deserialize )*
                         return dec.get_pos();
                     }
+
+                    fn try_unpack(&mut self, data: &[u8]) -> ::core::result::Result<usize, ::rust_chain::serializer::DecodeError> {
+                        let mut dec = ::rust_chain::serializer::Decoder::new(data);
+                        #( #try_deserialize )*
+                        Ok(dec.get_pos())
+                    }
                 }
             );
             quote_spanned!(span =>
@@ -618,6 +633,22 @@ impl Contract {
                 }
             });
 
+            let try_deserialize = item.sig.inputs.iter().map(|arg| {
+                if let syn::FnArg::Typed(pat_type) = arg {
+                    let span = arg.span();
+                    let ty = &*pat_type.ty;
+                    if let syn::Pat::Ident(x) = &*pat_type.pat {
+                        quote_spanned!(span=>
+                            dec.try_unpack::<#ty>(&mut self.#x)?;
+                        )
+                    } else {
+                        quote!{}
+                    }
+                } else {
+                    quote!{}
+                }
+            });
+
             let get_size = item.sig.inputs.iter().map(|arg| {
                 if let syn::FnArg::Typed(pat_type) = arg {
                     let span = arg.span();
@@ -659,6 +690,13 @@ impl Contract {
                         #( #deserialize )*
                         return dec.get_pos();
                     }
+
+                    fn try_unpack(&mut self, data: &[u8]) -> ::core::result::Result<usize, ::rust_chain::serializer::DecodeError> {
+                        #[allow(unused_mut)]
+                        let mut dec = ::rust_chain::serializer::Decoder::new(data);
+                        #( #try_deserialize )*
+                        Ok(dec.get_pos())
+                    }
                 }
             );
 
@@ -1541,6 +1579,28 @@ impl Contract {
                     }
             });
 
+            let try_unpack_code = item.variants
+                .iter()
+                .enumerate()
+                .map(|(i,field)| {
+                    let field_ident = &field.ident;
+                    let index = syn::LitInt::new(&i.to_string(), proc_macro2::Span::call_site());
+                    if let syn::Fields::Unnamed(x) = &field.fields {
+                        let ty = &x.unnamed.last().unwrap().ty;
+                        quote!{
+                            #index => {
+                                let mut v: #ty = Default::default();
+                                dec.try_unpack(&mut v)?;
+                                *self = #variant_ident::#field_ident(v);
+                            }
+                        }
+                    } else {
+                        quote!{}
+                    }
+            });
+
+            let variant_name = proc_macro2::Literal::string(&variant_ident.to_string());
+
             let getsize_code = item.variants
                 .iter()
                 .map(|field| {
@@ -1593,6 +1653,19 @@ impl Contract {
                         }
                         return dec.get_pos();
                     }
+
+                    fn try_unpack(&mut self, data: &[u8]) -> ::core::result::Result<usize, ::rust_chain::serializer::DecodeError> {
+                        let mut dec = ::rust_chain::serializer::Decoder::new(data);
+                        let mut variant_type_index: u8 = 0;
+                        dec.try_unpack(&mut variant_type_index)?;
+                        match variant_type_index {
+                            #( #try_unpack_code )*
+                            _ => {
+                                return Err(::rust_chain::serializer::DecodeError::BadVariant { name: #variant_name, tag: variant_type_index as u32 });
+                            }
+                        }
+                        Ok(dec.get_pos())
+                    }
                 }
             )
         });
@@ -1,6 +1,31 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Fields};
+use syn::{parse_macro_input, DeriveInput, Fields, Field};
+
+fn is_binary_extension(field: &Field) -> bool {
+    if let syn::Type::Path(type_path) = &field.ty {
+        if let Some(seg) = type_path.path.segments.last() {
+            return seg.ident == "BinaryExtension";
+        }
+    }
+    false
+}
+
+/// Binary extensions are optional trailing data, so no other field can follow them
+fn check_binary_extension_order<'a>(fields: impl IntoIterator<Item = &'a Field>) -> syn::Result<()> {
+    let mut extension: Option<&Field> = None;
+    for field in fields {
+        if is_binary_extension(field) {
+            extension = Some(field);
+        } else if let Some(extension) = extension {
+            return Err(syn::Error::new_spanned(
+                extension,
+                "BinaryExtension fields can only be followed by other BinaryExtension fields",
+            ));
+        }
+    }
+    Ok(())
+}
 
 #[proc_macro_derive(StructPacker)]
 pub fn your_macro_name_derive(input: TokenStream) -> TokenStream {
@@ -18,6 +43,10 @@ pub fn your_macro_name_derive(input: TokenStream) -> TokenStream {
         _ => panic!("StructPacker can only be derived for structs"),
     };
 
+    if let Err(err) = check_binary_extension_order(&fields) {
+        return TokenStream::from(err.to_compile_error());
+    }
+
     let size_fields = fields.iter().map(|f| {
         let field_name = &f.ident;
         quote! {
@@ -39,6 +68,13 @@ pub fn your_macro_name_derive(input: TokenStream) -> TokenStream {
         }
     });
 
+    let try_unpack_fields = fields.iter().map(|f| {
+        let field_name = &f.ident;
+        quote! {
+            dec.try_unpack(&mut self.#field_name)?;
+        }
+    });
+
     let expanded = quote! {
         // Generate the code to be added
         impl Packer for #name {
@@ -59,9 +95,48 @@ pub fn your_macro_name_derive(input: TokenStream) -> TokenStream {
                 #(#unpack_fields)*
                 dec.get_pos()
             }
+
+            fn try_unpack(&mut self, data: &[u8]) -> ::core::result::Result<usize, ::rust_chain::serializer::DecodeError> {
+                let mut dec = Decoder::new(data);
+                #(#try_unpack_fields)*
+                Ok(dec.get_pos())
+            }
         }
     };
 
     // Return the generated implementation
     TokenStream::from(expanded)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn check(input: DeriveInput) -> syn::Result<()> {
+        match input.data {
+            syn::Data::Struct(s) => check_binary_extension_order(&s.fields),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_binary_extension_order() {
+        assert!(check(parse_quote! {
+            struct A {
+                a: u64,
+                b: BinaryExtension<u64>,
+                c: rust_chain::BinaryExtension<String>,
+            }
+        }).is_ok());
+
+        let err = check(parse_quote! {
+            struct B {
+                a: u64,
+                b: BinaryExtension<u64>,
+                c: u64,
+            }
+        }).unwrap_err();
+        assert!(err.to_string().contains("BinaryExtension fields can only be followed"));
+    }
+}
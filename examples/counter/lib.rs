@@ -4,9 +4,30 @@
 mod token {
     use rust_chain::{
         Name,
+        Transaction,
+        TimePointSec,
+        action::{
+            Action,
+            PermissionLevel,
+        },
+        serializer::Encoder,
+        current_time,
+        read_transaction,
+        sha256,
+        name,
+        ACTIVE,
         chain_println,
     };
 
+    #[chain(packer)]
+    struct Inc {
+    }
+
+    #[chain(packer)]
+    struct SaveTrx {
+        key: u64,
+    }
+
     #[chain(table="counter")]
     pub struct Counter {
         #[chain(primary)]
@@ -14,6 +35,13 @@ mod token {
         count: u64
     }
 
+    #[chain(table="trx")]
+    pub struct Trx {
+        #[chain(primary)]
+        key: u64,
+        packed: Vec<u8>,
+    }
+
     #[chain(main)]
     pub struct Contract {
         receiver: Name,
@@ -43,5 +71,42 @@ mod token {
                 chain_println!("count is", 1);
             }
         }
+
+        #[chain(action = "incdelayed")]
+        pub fn inc_delayed(&self, delay_sec: u32) {
+            let expiration = TimePointSec::from(current_time()).seconds + delay_sec + 60;
+            let mut trx = Transaction::new(expiration, delay_sec);
+            let perm = PermissionLevel{actor: self.receiver, permission: ACTIVE};
+            trx.add_action(Action::new(self.receiver, name!("inc"), perm, &Inc{}));
+            trx.send(self.receiver, 1, true);
+        }
+
+        /// Hashes `rounds` times, to use cpu
+        #[chain(action = "burncpu")]
+        pub fn burn_cpu(&self, rounds: u32) {
+            let mut hash = [0u8; 32];
+            for _ in 0..rounds {
+                hash = sha256(&hash).data;
+            }
+            chain_println!("hash is", hash[0]);
+        }
+
+        /// Stores the packed transaction executing this action at `key`
+        #[chain(action = "savetrx")]
+        pub fn save_trx(&self, key: u64) {
+            let db = Trx::new_table(self.receiver);
+            let packed = Encoder::pack(&read_transaction());
+            db.store(&Trx{key, packed}, self.receiver);
+        }
+
+        /// Sends a deferred transaction with `key` as sender id, which stores itself at `key`
+        #[chain(action = "savedelayed")]
+        pub fn save_delayed(&self, key: u64) {
+            let expiration = TimePointSec::from(current_time()).seconds + 60;
+            let mut trx = Transaction::new(expiration, 0);
+            let perm = PermissionLevel{actor: self.receiver, permission: ACTIVE};
+            trx.add_action(Action::new(self.receiver, name!("savetrx"), perm, &SaveTrx{key}));
+            trx.send(self.receiver, key as u128, true);
+        }
     }
 }
@@ -31,10 +31,13 @@ fn native_apply(receiver: u64, first_receiver: u64, action: u64) {
 #[cfg(test)]
 mod tests {
 
-    use rust_chain::ChainTester;
+    use rust_chain::{ChainTester, Name, Asset};
     use rust_chain::serializer::Packer as _;
+    use rust_chain::system_actions::Transfer;
+    use rust_chain::typed_action::push_action_typed;
     use rust_chain::chaintester::{
         GetTableRowsPrams,
+        TransactionReturn,
     };
     use std::{
         fs,
@@ -47,6 +50,10 @@ mod tests {
         tester.deploy_contract("hello", wasm_file, abi_file).unwrap();
     }
 
+    fn get_balance(tester: &mut ChainTester, account: &str, token_account: &str, code: &str) -> String {
+        rust_chain::table_rows::get_balance(tester, account, token_account, rust_chain::SymbolCode::new(code)).unwrap().to_string()
+    }
+
     fn update_auth(tester: &mut ChainTester) {
         let updateauth_args = r#"{
             "account": "hello",
@@ -72,7 +79,7 @@ mod tests {
         "#;
 
         tester.push_action("eosio", "updateauth", updateauth_args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -97,10 +104,267 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
+
+        // Counter{key: 1, count: 2}
+        let mut enc = rust_chain::serializer::Encoder::new(16);
+        1u64.pack(&mut enc);
+        2u64.pack(&mut enc);
+        let raw = tester.get_table_row_raw("hello", "", "counter", 1).unwrap();
+        assert_eq!(raw, enc.get_bytes());
+        assert!(tester.get_table_row_raw("hello", "", "counter", 2).is_err());
+    }
+
+    #[test]
+    fn test_set_tapos_from() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        update_auth(&mut tester);
+        for _ in 0..3 {
+            tester.produce_block().unwrap();
+        }
+        let info = tester.get_info().unwrap();
+        let head_block_num = info["head_block_num"].as_u64().unwrap();
+
+        let mut trx = rust_chain::Transaction::default();
+        trx.set_tapos_from(&mut tester, 1, 60).unwrap();
+        assert_eq!(trx.ref_block_num(), ((head_block_num - 1) & 0xffff) as u32);
+        // bytes 8 to 12 of the block id, little endian
+        let id = tester.get_block_id(head_block_num - 1).unwrap();
+        assert_eq!(trx.ref_block_prefix(), u32::from_str_radix(&id[16..24], 16).unwrap().swap_bytes());
+        let head_block_time = rust_chain::TimePointSec::from_iso_string(&info["head_block_time"].as_str().unwrap()[..19]).unwrap();
+        assert_eq!(trx.expiration().seconds, head_block_time.seconds + 60);
+
+        assert!(trx.set_tapos_from(&mut tester, head_block_num as u32, 60).is_err());
+
+        // the debugger server can't push a transaction built locally, so the TaPoS fields are checked against
+        // the ones nodeos accepted for a transaction pushed by the server, which `savetrx` stores at key 3
+        tester.push_action("hello", "savetrx", r#"{"key": 3}"#.into(), r#"{"hello": "active"}"#).unwrap();
+        tester.produce_block().unwrap();
+        let pushed = saved_transaction(&mut tester, 3);
+        let blocks_behind = (0..head_block_num as u32).find(|blocks_behind| {
+            trx.set_tapos_from(&mut tester, *blocks_behind, 60).is_ok() && trx.ref_block_num() == pushed.ref_block_num()
+        });
+        assert!(blocks_behind.is_some(), "block {} referenced by the pushed transaction is unknown", pushed.ref_block_num());
+        assert_eq!(trx.ref_block_prefix(), pushed.ref_block_prefix());
+    }
+
+    // the transaction stored by the `savetrx` action of counter at `key`, as read back from nodeos
+    fn saved_transaction(tester: &mut ChainTester, key: u64) -> rust_chain::Transaction {
+        // Trx{key, packed}
+        let raw = tester.get_table_row_raw("hello", "", "trx", key).unwrap();
+        let mut packed: Vec<u8> = Vec::new();
+        packed.unpack(&raw[8..]);
+        let mut trx = rust_chain::Transaction::default();
+        assert_eq!(trx.unpack(&packed), packed.len());
+        trx
+    }
+
+    #[test]
+    fn test_transaction_id() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        update_auth(&mut tester);
+
+        let ret = tester.push_action("hello", "savetrx", r#"{"key": 1}"#.into(), r#"{"hello": "active"}"#).unwrap();
+        tester.produce_block().unwrap();
+
+        // the id computed locally from the transaction built by the server matches the one it returned
+        let trx = saved_transaction(&mut tester, 1);
+        assert_eq!(trx.actions().len(), 1);
+        let id = rust_chain::Checksum256::from_hex(ret["id"].as_str().unwrap());
+        assert!(trx.id() == id);
+    }
+
+    #[test]
+    fn test_transaction_extension_id() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        update_auth(&mut tester);
+
+        let permissions = r#"{"hello": "active"}"#;
+        let ret = tester.push_action("hello", "savetrx", r#"{"key": 1}"#.into(), permissions).unwrap();
+        tester.produce_block().unwrap();
+        let id = rust_chain::Checksum256::from_hex(ret["id"].as_str().unwrap());
+        let trx = saved_transaction(&mut tester, 1);
+        assert!(trx.extension().is_empty());
+        assert!(trx.id() == id);
+
+        let mut with_extension = trx.clone();
+        with_extension.add_extension(0, vec![1]);
+        assert!(with_extension.id() != id);
+
+        // nodeos adds a deferred_transaction_generation_context extension to a deferred transaction:
+        // the id of the sending transaction, the sender id and the sender. It doesn't return
+        // the id of a deferred transaction, the one it executed is read back instead.
+        let ret = tester.push_action("hello", "savedelayed", r#"{"key": 2}"#.into(), permissions).unwrap();
+        tester.produce_until(|tester| tester.get_table_row_raw("hello", "", "trx", 2).is_ok()).unwrap();
+        let deferred = saved_transaction(&mut tester, 2);
+
+        let mut enc = rust_chain::serializer::Encoder::new(56);
+        rust_chain::Checksum256::from_hex(ret["id"].as_str().unwrap()).pack(&mut enc);
+        2u128.pack(&mut enc);
+        Name::new("hello").pack(&mut enc);
+        let context = rust_chain::TransactionExtension::new(0, enc.get_bytes().to_vec());
+        assert!(deferred.extension()[..] == [context]);
+        assert_eq!(deferred.actions().len(), 1);
+    }
+
+    #[test]
+    fn test_push_action_with_deadline() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        update_auth(&mut tester);
+
+        let permissions = r#"{"hello": "active"}"#;
+        let ret = tester.push_action_with_deadline("hello", "burncpu", r#"{"rounds": 100000}"#.into(), permissions, 200).unwrap();
+        ret.restore.unwrap();
+        let err = ret.result.unwrap_err();
+        let name = err.error_name().unwrap_or_default();
+        assert!(name == "tx_cpu_usage_exceeded" || name == "deadline_exception", "unexpected error {}", err);
+
+        // the cpu limit of the chain is restored
+        tester.push_action("hello", "burncpu", r#"{"rounds": 100}"#.into(), permissions).unwrap();
+    }
+
+    #[test]
+    fn test_abi_matches() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        tester.assert_abi_matches("hello", "./counter/counter.abi.json");
+    }
+
+    #[test]
+    #[should_panic(expected = "structs Counter: expected")]
+    fn test_abi_mismatch() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+
+        let mut golden: serde_json::Value = serde_json::from_str(&fs::read_to_string("./counter/counter.abi.json").unwrap()).unwrap();
+        golden["structs"][0]["fields"][1]["type"] = "uint32".into();
+        let changed = std::env::temp_dir().join(format!("counter-{}.abi.json", std::process::id()));
+        fs::write(&changed, golden.to_string()).unwrap();
+        tester.assert_abi_matches("hello", changed.to_str().unwrap());
+    }
+
+    #[test]
+    fn test_duplicate_rejected() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        update_auth(&mut tester);
+
+        // savetrx stores its row without looking it up first, the table rejects the duplicate
+        tester.assert_duplicate_rejected("hello", "savetrx", r#"{"key": 1}"#, r#"{"hello": "active"}"#, "1");
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate key")]
+    fn test_duplicate_accepted() {
+        let abi = &secondaryindex::generate_abi();
+        fs::write(Path::new("./secondaryindex/target/secondaryindex.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "secondaryindex");
+        update_auth(&mut tester);
+
+        // test1 updates the row of an existing key
+        tester.assert_duplicate_rejected("hello", "test1", r#"{"key": 1, "value": 10}"#, r#"{"hello": "active"}"#, "1");
+    }
+
+    #[test]
+    fn test_signing_digest() {
+        // sha256 and decode_hex need the vm api server
+        let _tester = ChainTester::new();
+        // eosio.token::transfer of 1.0000 EOS from alice to bob with memo "hi", authorized by alice@active,
+        // expiring at 2023-05-01T12:30:00 with ref_block_num 0x1234 and ref_block_prefix 0xdeadbeef.
+        // cleos can't be run here, so the expected digest was computed with python's hashlib
+        // over the mainnet chain id, these bytes and 32 zero bytes
+        let packed = rust_chain::utils::decode_hex("c8b04f643412efbeadde000000000100a6823403ea3055000000572d3ccdcd010000000000855c3400000000a8ed3232230000000000855c340000000000000e3d102700000000000004454f530000000002686900");
+        let mut trx = rust_chain::Transaction::default();
+        assert_eq!(trx.unpack(&packed), packed.len());
+
+        let transfer = Transfer {
+            from: Name::new("alice"),
+            to: Name::new("bob"),
+            quantity: Asset::from_string("1.0000 EOS"),
+            memo: "hi".into(),
+        };
+        let action = rust_chain::Action::new(
+            Name::new("eosio.token"),
+            Name::new("transfer"),
+            rust_chain::PermissionLevel::new(Name::new("alice"), Name::new("active")),
+            &transfer,
+        );
+        assert_eq!(trx.expiration().seconds, 1682944200);
+        assert_eq!(trx.ref_block_num(), 0x1234);
+        assert_eq!(trx.ref_block_prefix(), 0xdeadbeef);
+        assert_eq!(trx.actions().len(), 1);
+        assert!(trx.actions()[0] == action);
+        assert_eq!(rust_chain::serializer::Encoder::pack(&trx), packed);
+
+        let chain_id = rust_chain::Checksum256::from_hex("aca376f206b8fc25a6ed44dbdc66547c36c6c33e3a119ffbeaef943642f0e906");
+        let expected = rust_chain::Checksum256::from_hex("34d4fda701191c72761d78309ca96f11b7ca113203b037a3862c1c5dcead5c5d");
+        assert!(trx.signing_digest(&chain_id) == expected);
+        let id = rust_chain::Checksum256::from_hex("3b9dfc59598fe96b96e2ef3b541e5088943ea5ffa1b16958f9db814af98fd790");
+        assert!(trx.id() == id);
+    }
+
+    #[test]
+    fn test_checksum_from_parts() {
+        // sha256 needs the vm api server
+        let _tester = ChainTester::new();
+        // sha256 of the little endian bytes of `alice` (0x345c850000000000) followed by those of 42u64
+        let key = rust_chain::Checksum256::from_parts(&[&Name::new("alice"), &42u64]);
+        let expected = rust_chain::Checksum256::from_hex("89222b6fa5ef91ae00b3077fdd31d2169b632eb2cbcc0d9042485cd27773bdb6");
+        assert!(key == expected);
+        assert!(rust_chain::Checksum256::from_parts(&[&42u64, &Name::new("alice")]) != expected);
+    }
+
+    #[test]
+    fn test_produce_until_deferred() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        update_auth(&mut tester);
+
+        let permissions = r#"
+        {
+            "hello": "active"
+        }
+        "#;
+        tester.push_action("hello", "incdelayed", r#"{"delay_sec": 3}"#.into(), permissions).unwrap();
+
+        let has_counter = |tester: &mut ChainTester| tester.get_table_row_raw("hello", "", "counter", 1).is_ok();
+        let blocks = tester.produce_until(has_counter).unwrap();
+        // 3 seconds are 6 blocks of 500ms
+        assert!(blocks >= 6);
+        assert!(tester.produce_until_ex(0, |_| false).is_err());
     }
 
     #[test]
@@ -125,10 +389,10 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -151,7 +415,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     
         // let ret = tester.get_table_rows(true, "hello", "hello", "counter", "", "", 10).unwrap();
         // println!("+++++++=ret:{:?}", ret);
@@ -159,7 +423,7 @@ mod tests {
         // println!("+++++++=ret:{:?}", ret.get("rows").unwrap());
 
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -180,8 +444,9 @@ mod tests {
             "hello": "active"
         }
         "#;
-        tester.push_action("hello", "sayhello", args.into(), permissions).unwrap();
-        tester.produce_block();
+        let ret = tester.push_action("hello", "sayhello", args.into(), permissions).unwrap();
+        tester.produce_block().unwrap();
+        TransactionReturn::new(ret).assert_no_inline_actions();
     }
 
     #[test]
@@ -206,7 +471,7 @@ mod tests {
         "#;
 
         tester.push_action("hello", "test1", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         args = r#"
         {
@@ -216,7 +481,7 @@ mod tests {
         "#;
 
         tester.push_action("hello", "test1", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         args = r#"
         {
@@ -225,7 +490,7 @@ mod tests {
         "#;
 
         tester.push_action("hello", "test2", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         args = r#"
         {
@@ -234,7 +499,129 @@ mod tests {
         "#;
 
         tester.push_action("hello", "test2", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
+
+        // key 1 => 11, key 2 => 24
+        let keys = tester.get_table_by_secondary("hello", "", "counter", "2", "10", "30", "key").unwrap();
+        assert_eq!(keys, vec![1, 2]);
+        let keys = tester.get_table_by_secondary("hello", "", "counter", "2", "20", "30", "key").unwrap();
+        assert_eq!(keys, vec![2]);
+        let keys = tester.get_table_by_secondary("hello", "", "counter", "2", "25", "30", "key").unwrap();
+        assert!(keys.is_empty());
+        assert!(tester.get_table_by_secondary("hello", "", "counter", "2", "10", "30", "id").is_err());
+
+        tester.assert_table_equals("hello", "", "counter", serde_json::json!([
+            {"key": 1, "value": 11},
+            {"key": 2, "value": 24},
+        ]));
+    }
+
+    #[test]
+    fn test_block_transaction_order() {
+        let abi = &secondaryindex::generate_abi();
+        fs::write(Path::new("./secondaryindex/target/secondaryindex.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "secondaryindex");
+        tester.produce_block().unwrap();
+
+        let permissions = r#"{"hello": "active"}"#;
+        let first = tester.push_action("hello", "test1", r#"{"key": 2, "value": 22}"#.into(), permissions).unwrap();
+        let second = tester.push_action("hello", "test1", r#"{"key": 1, "value": 11}"#.into(), permissions).unwrap();
+        let block_num = first["block_num"].as_u64().unwrap() as u32;
+        assert_eq!(second["block_num"], first["block_num"]);
+        tester.produce_block().unwrap();
+
+        let ids = rust_chain::transaction::get_block_transaction_order(&mut tester, block_num).unwrap();
+        let expected: Vec<rust_chain::Checksum256> = [&first, &second].iter().map(|tx| tx["id"].as_str().unwrap().parse().unwrap()).collect();
+        assert!(ids == expected);
+        assert!(rust_chain::transaction::get_block_transaction_order(&mut tester, block_num + 1).is_err());
+    }
+
+    #[test]
+    fn test_get_chain_id() {
+        let mut tester = ChainTester::new();
+        let chain_id = rust_chain::transaction::get_chain_id(&mut tester).unwrap();
+        assert!(chain_id != rust_chain::Checksum256::default());
+        assert_eq!(tester.get_info().unwrap()["chain_id"], chain_id.to_string());
+    }
+
+    #[test]
+    fn test_transaction_producer() {
+        let mut tester = ChainTester::new();
+        let args = r#"{"account": "hello", "is_priv": 0}"#;
+        let ret = tester.push_action("eosio", "setpriv", args.into(), r#"{"eosio": "active"}"#).unwrap();
+        let id: rust_chain::Checksum256 = ret["id"].as_str().unwrap().parse().unwrap();
+        assert!(rust_chain::transaction::transaction_producer(&mut tester, &id).is_err());
+
+        tester.produce_block().unwrap();
+        let producer = tester.get_head_block_producer().unwrap();
+        assert_eq!(rust_chain::transaction::transaction_producer(&mut tester, &id).unwrap(), rust_chain::Name::from_str(&producer));
+    }
+
+    fn insert_rows(tester: &mut ChainTester, keys: std::ops::Range<u64>) {
+        for key in keys {
+            let args = serde_json::json!({"key": key, "value": key * 10});
+            tester.push_action("hello", "test1", args.to_string().into(), r#"{"hello": "active"}"#).unwrap();
+        }
+        tester.produce_block().unwrap();
+    }
+
+    #[test]
+    fn test_ram_usage_below() {
+        let abi = &secondaryindex::generate_abi();
+        fs::write(Path::new("./secondaryindex/target/secondaryindex.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "secondaryindex");
+        tester.produce_block().unwrap();
+        let threshold = tester.get_ram_usage("hello").unwrap() + 4096;
+
+        for i in 0..3 {
+            insert_rows(&mut tester, i * 2..i * 2 + 2);
+            assert!(tester.get_ram_usage("hello").unwrap() < threshold);
+            tester.assert_ram_usage_below("hello", threshold);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "ram usage of hello exceeds threshold")]
+    fn test_ram_usage_exceeded() {
+        let abi = &secondaryindex::generate_abi();
+        fs::write(Path::new("./secondaryindex/target/secondaryindex.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "secondaryindex");
+        tester.produce_block().unwrap();
+        let threshold = tester.get_ram_usage("hello").unwrap() + 4096;
+
+        // each row and its secondary index entry take a few hundred bytes
+        insert_rows(&mut tester, 0..40);
+        tester.assert_ram_usage_below("hello", threshold);
+    }
+
+    #[test]
+    fn test_idempotent_upsert() {
+        let abi = &secondaryindex::generate_abi();
+        fs::write(Path::new("./secondaryindex/target/secondaryindex.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "secondaryindex");
+        let permissions = r#"{"hello": "active"}"#;
+        let args = r#"{"key": 1, "value": 11}"#;
+        tester.assert_idempotent("hello", "test1", args, permissions, &[("", "counter")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not idempotent")]
+    fn test_not_idempotent_counter() {
+        let abi = &counter::generate_abi();
+        fs::write(Path::new("./counter/target/counter.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        deploy_contract(&mut tester, "counter");
+        let permissions = r#"{"hello": "active"}"#;
+        tester.assert_idempotent("hello", "inc", "{}", permissions, &[("", "counter")]);
     }
 
     #[test]
@@ -256,10 +643,10 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -281,8 +668,24 @@ mod tests {
             "hello": "active"
         }
         "#;
-        tester.push_action("hello", "sayhello", args.into(), permissions).unwrap();
-        tester.produce_block();
+        let ret = tester.push_action("hello", "sayhello", args.into(), permissions).unwrap();
+        tester.produce_block().unwrap();
+        // sayhello sends saygoodbye
+        assert_eq!(TransactionReturn::new(ret).inline_action_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "expect no inline actions, got 1")]
+    fn test_inlineaction_not_allowed() {
+        let abi = &inlineaction::generate_abi();
+        fs::write(Path::new("./inlineaction/target/inlineaction.abi"), abi).unwrap();
+
+        let mut tester = ChainTester::new();
+        update_auth(&mut tester);
+        deploy_contract(&mut tester, "inlineaction");
+        let permissions = r#"{"hello": "active"}"#;
+        let ret = tester.push_action("hello", "sayhello", r#"{"name": "bob"}"#.into(), permissions).unwrap();
+        TransactionReturn::new(ret).assert_no_inline_actions();
     }
 
     #[test]
@@ -313,8 +716,112 @@ mod tests {
             "hello": "active"
         }
         "#;
-        tester.push_action("alice", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        let (_, notifications) = tester.push_action_with_notifications("alice", "test", args.into(), permissions).unwrap();
+        tester.produce_block().unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!((notifications[0].receiver.as_str(), notifications[0].account.as_str()), ("hello", "alice"));
+        assert_eq!(notifications[0].action, "test");
+        assert!(notifications[0].inline_actions.is_empty());
+    }
+
+    #[test]
+    fn test_supply_conserved() {
+        let mut tester = ChainTester::new();
+        let abi = token::generate_abi();
+        fs::write(Path::new("./token/target/token.abi"), &abi).unwrap();
+        tester.deploy_contract("hello", "./token/target/token.wasm", "./token/target/token.abi").unwrap();
+
+        let permissions = r#"{"hello": "active"}"#;
+        tester.push_action("hello", "create", r#"{"issuer": "hello", "maximum_supply": "100.0000 EOS"}"#.into(), permissions).unwrap();
+        tester.push_action("hello", "issue", r#"{"to": "hello", "quantity": "10.0000 EOS", "memo": ""}"#.into(), permissions).unwrap();
+        tester.produce_block().unwrap();
+        assert!(tester.is_supply_conserved("hello", "EOS", &["hello", "alice"]));
+
+        let transfers = [("hello", "alice", "3.0000 EOS"), ("alice", "hello", "1.0000 EOS"), ("hello", "alice", "0.5000 EOS")];
+        for (from, to, quantity) in transfers {
+            tester.transfer("hello", from, to, quantity, "").unwrap();
+            tester.produce_block().unwrap();
+            assert!(tester.is_supply_conserved("hello", "EOS", &["hello", "alice"]));
+        }
+        assert_eq!(tester.get_balance_raw("alice", "hello", "EOS").unwrap(), 25000);
+        assert_eq!(tester.get_supply("hello", "EOS"), 100000);
+        // a missing holder breaks the sum
+        assert!(!tester.is_supply_conserved("hello", "EOS", &["hello"]));
+    }
+
+    #[test]
+    fn test_contract_info() {
+        let mut tester = ChainTester::new();
+        let abi = token::generate_abi();
+        fs::write(Path::new("./token/target/token.abi"), &abi).unwrap();
+        tester.deploy_contract("hello", "./token/target/token.wasm", "./token/target/token.abi").unwrap();
+
+        let abi: serde_json::Value = serde_json::from_str(&abi).unwrap();
+        let names = |key: &str| -> Vec<String> {
+            abi[key].as_array().unwrap().iter().map(|item| item["name"].as_str().unwrap().to_string()).collect()
+        };
+        let info = tester.get_contract_info("hello").unwrap();
+        assert_eq!(info.abi_version, abi["version"].as_str().unwrap());
+        assert_eq!(info.actions, names("actions"));
+        assert_eq!(info.tables, names("tables"));
+        for action in ["create", "issue", "retire", "transfer", "open", "close"] {
+            assert!(info.actions.iter().any(|a| a == action), "{}", action);
+        }
+        assert_eq!(info.code_hash.len(), 64);
+        assert_eq!(info.code_hash, tester.deployed_code_hash("hello").unwrap());
+        assert!(tester.get_contract_info("alice").is_err());
+    }
+
+    #[test]
+    fn test_native_apply_notification() {
+        use rust_chain::apply_context::{ApplyContext, set_native_apply_context};
+        use std::sync::Mutex;
+
+        static CONTEXTS: Mutex<Vec<ApplyContext>> = Mutex::new(Vec::new());
+        fn apply(ctx: &ApplyContext) {
+            CONTEXTS.lock().unwrap().push(*ctx);
+        }
+
+        let mut tester = ChainTester::new();
+        let abi = sender::generate_abi();
+        fs::write(Path::new("./notify/sender/target/sender.abi"), &abi).unwrap();
+        let abi = receiver::generate_abi();
+        fs::write(Path::new("./notify/receiver/target/receiver.abi"), &abi).unwrap();
+        tester.deploy_contract("alice", "./notify/sender/target/sender.wasm", "./notify/sender/target/sender.abi").unwrap();
+        tester.deploy_contract("hello", "./notify/receiver/target/receiver.wasm", "./notify/receiver/target/receiver.abi").unwrap();
+        set_native_apply_context(&mut tester, "hello", Some(apply)).unwrap();
+
+        tester.push_action("alice", "test", r#"{"name": "bob"}"#.into(), r#"{"hello": "active"}"#).unwrap();
+        tester.produce_block().unwrap();
+
+        let contexts = CONTEXTS.lock().unwrap();
+        assert_eq!(contexts.len(), 1);
+        assert!(contexts[0].is_notification());
+        assert_eq!((contexts[0].receiver, contexts[0].first_receiver), (Name::new("hello"), Name::new("alice")));
+        assert_eq!(contexts[0].action, Name::new("test"));
+    }
+
+    #[test]
+    fn test_balance_of_other_symbol() {
+        let mut tester = ChainTester::new();
+        let abi = token::generate_abi();
+        fs::write(Path::new("./token/target/token.abi"), &abi).unwrap();
+        tester.deploy_contract("hello", "./token/target/token.wasm", "./token/target/token.abi").unwrap();
+
+        let permissions = r#"{"hello": "active"}"#;
+        tester.push_action("hello", "create", r#"{"issuer": "hello", "maximum_supply": "100.0000 EOS"}"#.into(), permissions).unwrap();
+        tester.push_action("hello", "create", r#"{"issuer": "hello", "maximum_supply": "100.00 ZZZ"}"#.into(), permissions).unwrap();
+        tester.push_action("hello", "issue", r#"{"to": "hello", "quantity": "2.00 ZZZ", "memo": ""}"#.into(), permissions).unwrap();
+        tester.produce_block().unwrap();
+        tester.transfer("hello", "hello", "alice", "2.00 ZZZ", "").unwrap();
+        tester.produce_block().unwrap();
+
+        // alice only holds ZZZ, which is the first row after EOS in her accounts table
+        assert_eq!(get_balance(&mut tester, "alice", "hello", "ZZZ"), "2.00 ZZZ");
+        assert_eq!(get_balance(&mut tester, "alice", "hello", "EOS"), "0.0000 EOS");
+        assert_eq!(tester.get_balance_raw("alice", "hello", "EOS").unwrap(), 0);
+        let unknown = rust_chain::SymbolCode::new("XYZ");
+        assert!(rust_chain::table_rows::get_balance(&mut tester, "alice", "hello", unknown).is_err());
     }
 
     #[test]
@@ -340,8 +847,8 @@ mod tests {
             "maximum_supply": "100.0000 EOS"
         }
         "#;
-        tester.push_action("hello", "create", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.assert_requires_auth("hello", "create", args, "hello", "alice");
+        tester.produce_block().unwrap();
 
         let ret = tester.get_table_rows(true, "hello", "EOS", "stat", "", "", 1).unwrap();
         let row = &ret["rows"][0]["data"];
@@ -357,7 +864,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "issue", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let ret = tester.get_table_rows(true, "hello", "EOS", "stat", "", "", 1).unwrap();
         let row = &ret["rows"][0]["data"];
@@ -368,6 +875,16 @@ mod tests {
         let ret = tester.get_table_rows(true, "hello", "hello", "accounts", "", "", 1).unwrap();
         let row = &ret["rows"][0]["data"];
         assert!(row["balance"] == "1.0000 EOS");
+
+        assert_eq!(get_balance(&mut tester, "hello", "hello", "EOS"), "1.0000 EOS");
+        assert_eq!(tester.get_balance_raw("hello", "hello", "EOS").unwrap(), 10000);
+        assert_eq!(get_balance(&mut tester, "alice", "hello", "EOS"), "0.0000 EOS");
+
+        let summary: std::collections::HashMap<String, u64> = tester.table_summary_ex("hello", "hello").unwrap().into_iter().collect();
+        assert_eq!(summary.len(), 2);
+        assert_eq!((summary["accounts"], summary["stat"]), (1, 0));
+        let summary: std::collections::HashMap<String, u64> = tester.table_summary_ex("hello", "EOS").unwrap().into_iter().collect();
+        assert_eq!((summary["accounts"], summary["stat"]), (0, 1));
     
         let args = r#"
         {
@@ -378,6 +895,7 @@ mod tests {
         "#;
         let ret = tester.push_action("hello", "issue", args.into(), permissions).unwrap_err();
         ret.check_err("tokens can only be issued to issuer account");
+        ret.assert_code(3050003);
 
 
         let args = r#"
@@ -389,7 +907,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "transfer", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let ret = tester.get_table_rows(true, "hello", "hello", "accounts", "", "", 1).unwrap();
         assert!(ret["rows"][0]["data"]["balance"] == "0.0000 EOS");
@@ -398,22 +916,14 @@ mod tests {
         assert!(ret["rows"][0]["data"]["balance"] == "1.0000 EOS");
     
         //transfer back
-        let args = r#"
-        {
-            "from": "alice",
-            "to": "hello",
-            "quantity": "1.0000 EOS",
-            "memo": "transfer back"
-        }
-        "#;
-
+        let transfer = Transfer::new(Name::new("alice"), Name::new("hello"), Asset::from_string("1.0000 EOS"), "transfer back".into());
         let permissions_alice = r#"
         {
             "alice": "active"
         }
         "#;
-        tester.push_action("hello", "transfer", args.into(), permissions_alice).unwrap();
-        tester.produce_block();
+        push_action_typed(&mut tester, "hello", "transfer", &transfer, permissions_alice).unwrap();
+        tester.produce_block().unwrap();
 
         //retire
         let args = r#"
@@ -423,7 +933,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "retire", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let ret = tester.get_table_rows(true, "hello", "hello", "accounts", "", "", 1).unwrap();
         assert!(ret["rows"][0]["data"]["balance"] == "0.0000 EOS");
@@ -443,7 +953,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "open", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let r = tester.get_table_rows(true, "hello", "helloworld11", "accounts", "", "", 1).unwrap();
         assert!(r["rows"][0]["data"]["balance"] == "0.0000 EOS");
@@ -461,7 +971,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "close", args.into(), permissions_helloworld11).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let r = tester.get_table_rows(true, "hello", "helloworld11", "accounts", "", "", 1).unwrap();
         assert!(ret["rows"][0].is_null());
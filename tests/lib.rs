@@ -231,7 +231,7 @@ mod tests {
         "#;
 
         tester.push_action("eosio", "updateauth", updateauth_args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     pub fn init_test(test_case: &str) -> ChainTester {
@@ -277,7 +277,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "sayhello", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -300,7 +300,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let max_amount = (1i64 << 62) - 1;
 
@@ -313,7 +313,7 @@ mod tests {
             args.append(&mut "\x04EOS\x00\x00\x00\x00".as_bytes().to_vec());
             let ret = tester.push_action("hello", "test2", args.into(), permissions).unwrap_err();
             ret.check_err("Asset.unpack: bad asset amount");
-            tester.produce_block();
+            tester.produce_block().unwrap();
         }
 
         {
@@ -321,7 +321,7 @@ mod tests {
             args.append(&mut "\x04EOS\x00\x00\x00\x00".as_bytes().to_vec());
             let ret = tester.push_action("hello", "test2", args.into(), permissions).unwrap_err();
             ret.check_err("Asset.unpack: bad asset amount");
-            tester.produce_block();    
+            tester.produce_block().unwrap();    
         }
         {
             let args = r#"
@@ -331,7 +331,7 @@ mod tests {
             "#;
             let ret = tester.push_action("hello", "test3", args.into(), permissions).unwrap_err();
             ret.check_err("Asset.from_string: bad amount");
-            tester.produce_block();    
+            tester.produce_block().unwrap();    
         }
 
         {
@@ -342,7 +342,7 @@ mod tests {
             "#;
             let ret = tester.push_action("hello", "test3", args.into(), permissions).unwrap_err();
             ret.check_err("Asset.from_string: bad amount");
-            tester.produce_block();    
+            tester.produce_block().unwrap();    
         }
 
         let args = r#"
@@ -393,7 +393,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
 
@@ -415,7 +415,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -441,7 +441,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let args = r#"
         {
@@ -450,7 +450,7 @@ mod tests {
         "#;
         let err = tester.push_action("hello", "test2", args.into(), permissions).unwrap_err();
         err.check_err("bad name string");
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let args = r#"
         {
@@ -459,7 +459,7 @@ mod tests {
         "#;
         let err = tester.push_action("hello", "test2", args.into(), permissions).unwrap_err();
         err.check_err("bad name string");
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let names: [&str;3] = [
             "123451234512z",
@@ -474,11 +474,11 @@ mod tests {
             }}"#);
             let err = tester.push_action("hello", "test2", args.into(), permissions).unwrap_err();
             err.check_err("bad name string");
-            tester.produce_block();    
+            tester.produce_block().unwrap();    
         }
 
         tester.push_action("hello", "test3", "".into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -501,11 +501,11 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let old_balance = tester.get_balance("hello");
         tester.push_action("hello", "test2", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
         assert_eq!(old_balance, tester.get_balance("hello") + 10000);
 
     }
@@ -531,7 +531,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let args = r#"
             {
@@ -539,7 +539,7 @@ mod tests {
             }
         "#;
         tester.push_action("hello", "test2", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -567,7 +567,7 @@ mod tests {
         }
         "#;
         tester.push_action("alice", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -589,7 +589,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -647,7 +647,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -669,10 +669,10 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test1", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "test2", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.free();
 
@@ -689,76 +689,52 @@ mod tests {
         let mydata1 = testmi::testmi::MyData{a1: 11, a2: 22, a3: 33, a4: Uint256::new(0, 5), a5: 5.0, a6: a6_6};
         tester.push_action("hello", "teststore", Encoder::pack(&mydata1).into(), permissions).unwrap();
 
-        tester.produce_block();
-
-        let param = GetTableRowsPrams {
-            json: true,
-            code: "hello",
-            scope: "",
-            table: "mydata",
-            lower_bound: "0x000000000000000000000000000000000000000000000000000000000000000a",
-            upper_bound: "",
-            limit: 10,
-            key_type: "i256",
-            index_position: "4",
-            encode_type: "",
-            reverse: false,
-            show_payer: true,
-        };
+        tester.produce_block().unwrap();
+
+        let param = GetTableRowsPrams::builder()
+            .code("hello")
+            .table("mydata")
+            .lower_bound("0x000000000000000000000000000000000000000000000000000000000000000a")
+            .key_type("i256")
+            .index_position("4")
+            .show_payer(true)
+            .build();
         let ret = tester.get_table_rows_ex(&param).unwrap();
         assert_eq!(ret["rows"][1]["data"]["a4"], "0000000000000000000000000000000005000000000000000000000000000000");
 
         let mydata1 = testmi::testmi::MyData{a1: 11, a2: 22, a3: 33, a4: Uint256::new(0, 6), a5: 5.0, a6: a6_6};
         tester.push_action("hello", "testupdate", Encoder::pack(&mydata1).into(), permissions).unwrap();
 
-        let param = GetTableRowsPrams {
-            json: true,
-            code: "hello",
-            scope: "",
-            table: "mydata",
-            lower_bound: "0x000000000000000000000000000000000000000000000000000000000000000a",
-            upper_bound: "",
-            limit: 10,
-            key_type: "i256",
-            index_position: "4",
-            encode_type: "",
-            reverse: false,
-            show_payer: true,
-        };
+        let param = GetTableRowsPrams::builder()
+            .code("hello")
+            .table("mydata")
+            .lower_bound("0x000000000000000000000000000000000000000000000000000000000000000a")
+            .key_type("i256")
+            .index_position("4")
+            .show_payer(true)
+            .build();
         let ret = tester.get_table_rows_ex(&param).unwrap();
         assert_eq!(ret["rows"][1]["data"]["a4"], "0000000000000000000000000000000006000000000000000000000000000000");
 
-        let param = GetTableRowsPrams {
-            json: true,
-            code: "hello",
-            scope: "",
-            table: "mydata",
-            lower_bound: "10",
-            upper_bound: "",
-            limit: 10,
-            key_type: "i256",
-            index_position: "4",
-            encode_type: "",
-            reverse: false,
-            show_payer: true,
-        };
+        let param = GetTableRowsPrams::builder()
+            .code("hello")
+            .table("mydata")
+            .lower_bound("10")
+            .key_type("i256")
+            .index_position("4")
+            .show_payer(true)
+            .build();
         let ret = tester.get_table_rows_ex(&param).unwrap();
         assert_eq!(ret["rows"][1]["data"]["a4"], "0000000000000000000000000000000006000000000000000000000000000000");
 
-        let param = GetTableRowsPrams {
-            json: true,
-            code: "hello",
-            scope: "",
-            table: "mydata",
-            lower_bound: "000000000000000000000000000000000000000000000000000000000000000a",
-            upper_bound: "",
-            limit: 10,
-            key_type: "sha256",
-            index_position: "4",
-            encode_type: "",
-            reverse: false,
-            show_payer: true,
-        };
+        let param = GetTableRowsPrams::builder()
+            .code("hello")
+            .table("mydata")
+            .lower_bound("000000000000000000000000000000000000000000000000000000000000000a")
+            .key_type("sha256")
+            .index_position("4")
+            .show_payer(true)
+            .build();
         let ret = tester.get_table_rows_ex(&param).unwrap();
         assert_eq!(ret["rows"][1]["data"]["a4"], "0000000000000000000000000000000006000000000000000000000000000000");
     }
@@ -783,10 +759,10 @@ mod tests {
         "#;
 
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "test2", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -814,14 +790,14 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "test2", "".into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let err = tester.push_action("hello", "test3", "".into(), permissions).unwrap_err();
         err.check_err("bad hex characters");
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -843,11 +819,11 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let err = tester.push_action("hello", "test2", "".into(), permissions).unwrap_err();
         err.check_err("invalid utf8 string");
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -871,7 +847,7 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
     
     #[test]
@@ -895,7 +871,7 @@ mod tests {
         }
         "#;
         tester.push_action("eosio", "setpriv", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let args = r#"
         {
@@ -912,23 +888,23 @@ mod tests {
         }
         "#;
         tester.push_action("hello", "test", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let ret = tester.push_action("hello", "test2", "".into(), permissions).unwrap();
         if ret["action_traces"][0]["return_value"] != "68656c6c6f776f726c64" {//helloworld
             panic!("invalid return value");
         }
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         let info = tester.get_info().unwrap();
         let args = Encoder::pack(&testintrinsics::testintrinsics::test3{num: info["head_block_num"].as_u64().unwrap() as u32 + 1});
         tester.push_action("hello", "test3", args.into(), permissions).unwrap();
 
         tester.push_action("hello", "testctxfree", "".into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "testtime", "{}".into(), permissions).unwrap();
-        tester.produce_block_ex(10);
+        tester.produce_block_ex(10).unwrap();
 
         tester.push_action("hello", "testtime", "{}".into(), permissions).unwrap();
 
@@ -964,7 +940,7 @@ mod tests {
         // 1.000000e+00
         // 1.000000000000000e+00
         // 6.000000000000000000e+00
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -499,11 +499,11 @@ mod tests {
         "#;
 
         tester.push_action("eosio", "updateauth", updateauth_args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     
         let args = sayhello{name: "rust".into()};
         tester.push_action("hello", "sayhello", Encoder::pack(&args).into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
     #[test]
@@ -520,20 +520,20 @@ mod tests {
             deploy_contract(&mut tester);
 
             tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-            tester.produce_block();
+            tester.produce_block().unwrap();
 
             tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-            tester.produce_block();
+            tester.produce_block().unwrap();
         }
         {
             let mut tester = ChainTester::new();
             deploy_contract(&mut tester);
 
             tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-            tester.produce_block();
+            tester.produce_block().unwrap();
 
             tester.push_action("hello", "inc", args.into(), permissions).unwrap();
-            tester.produce_block();
+            tester.produce_block().unwrap();
 
         }
     }
@@ -104,7 +104,7 @@ mod tests {
 
         let args = saygoodbye::saygoodbye{name: "rust".into()};
         tester.push_action("hello", "saygoodbye", Encoder::pack(&args).into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 
 }
@@ -139,11 +139,11 @@ mod tests {
         "#;
 
         tester.push_action("eosio", "updateauth", updateauth_args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     
         let args = sayhello::sayhello{name: "rust".into()};
         let r = tester.push_action("hello", "sayhello", Encoder::pack(&args).into(), permissions).unwrap();
         println!("{:?}", r);
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 }
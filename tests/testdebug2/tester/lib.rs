@@ -122,13 +122,13 @@ mod tests {
         "#;
 
         tester.push_action("eosio", "updateauth", updateauth_args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     
         let args = sayhello::sayhello::sayhello{name: "rust".into()};
         tester.push_action("hello", "sayhello", Encoder::pack(&args).into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
 
         tester.push_action("hello", "sayhello", args.into(), permissions).unwrap();
-        tester.produce_block();
+        tester.produce_block().unwrap();
     }
 }